@@ -0,0 +1,36 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, LitStr};
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+    let mut name = LitStr::new(&ident.to_string(), ident.span());
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("asset"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                name = meta.value()?.parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported asset attribute"))
+            }
+        })?;
+    }
+
+    let gob = crate::gob();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            pub const ASSET_NAME: &'static str = #name;
+        }
+
+        impl #impl_generics #gob::asset::Asset for #ident #ty_generics #where_clause {
+            fn name() -> &'static str {
+                Self::ASSET_NAME
+            }
+        }
+    })
+}
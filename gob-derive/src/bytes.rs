@@ -0,0 +1,70 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{parse_quote, Data, DeriveInput, Index, Member};
+
+pub fn expand(mut input: DeriveInput) -> syn::Result<TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "IntoBytes can only be derived for structs",
+            ))
+        }
+    };
+
+    let members = fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index {
+                index: index as u32,
+                span: Span::call_site(),
+            }),
+        })
+        .collect::<Vec<_>>();
+    let values = (0..members.len())
+        .map(|index| format_ident!("field_{}", index))
+        .collect::<Vec<_>>();
+    let types = fields.iter().map(|field| &field.ty).collect::<Vec<_>>();
+
+    let gob = crate::gob();
+    for param in input.generics.type_params_mut() {
+        param
+            .bounds
+            .push(parse_quote!(#gob::asset::bytes::IntoBytes));
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #gob::asset::bytes::IntoBytes for #ident #ty_generics #where_clause {
+            fn into_bytes(&self) -> ::std::vec::Vec<u8> {
+                let mut bytes = ::std::vec::Vec::new();
+                #(
+                    let field = #gob::asset::bytes::IntoBytes::into_bytes(&self.#members);
+                    bytes.extend(#gob::asset::bytes::IntoBytes::into_bytes(&field.len()));
+                    bytes.extend(field);
+                )*
+                bytes
+            }
+
+            fn from_bytes(bytes: &[u8]) -> ::std::option::Option<Self> {
+                let mut bytes = bytes;
+                #(
+                    let len = <usize as #gob::asset::bytes::IntoBytes>::from_bytes(bytes.get(0..8)?)?;
+                    let end = len.checked_add(8)?;
+                    let #values = <#types as #gob::asset::bytes::IntoBytes>::from_bytes(bytes.get(8..end)?)?;
+                    bytes = &bytes[end..];
+                )*
+
+                if !bytes.is_empty() {
+                    return ::std::option::Option::None;
+                }
+
+                ::std::option::Option::Some(Self { #(#members: #values),* })
+            }
+        }
+    })
+}
@@ -0,0 +1,44 @@
+use proc_macro::TokenStream;
+use proc_macro_crate::{crate_name, FoundCrate};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, DeriveInput};
+
+mod asset;
+mod bytes;
+
+/// The path generated code uses to reach gob: `crate` inside gob itself, and
+/// the name it was imported under everywhere else.
+fn gob() -> proc_macro2::TokenStream {
+    match crate_name("gob") {
+        Ok(FoundCrate::Itself) => quote!(crate),
+        Ok(FoundCrate::Name(name)) => {
+            let name = format_ident!("{}", name);
+            quote!(::#name)
+        }
+        Err(_) => quote!(::gob),
+    }
+}
+
+/// Implements `Asset` for a type.
+///
+/// The type also gets an `ASSET_NAME` constant, which `Asset::name` returns.
+/// It defaults to the type's identifier and can be overridden with
+/// `#[asset(name = "...")]`.
+#[proc_macro_derive(Asset, attributes(asset))]
+pub fn derive_asset(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    asset::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements `IntoBytes` for a struct by encoding each field in declaration
+/// order, each prefixed with its encoded length. Decoding fails if a field is
+/// truncated or bytes are left over.
+#[proc_macro_derive(IntoBytes)]
+pub fn derive_into_bytes(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    bytes::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
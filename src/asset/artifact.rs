@@ -1,11 +1,76 @@
-use super::{bytes::IntoBytes, Asset, AssetId, AssetType};
-use std::{collections::HashSet, io::Read, path::Path};
+use super::{
+    bytes::IntoBytes,
+    io::{AssetIoError, FileReader},
+    Asset, AssetId, AssetType, Settings, SettingsType,
+};
+use std::{
+    collections::HashSet,
+    io::{Read, Write},
+    path::Path,
+};
 
-#[derive(Clone, Debug, Default)]
+/// A digest of an asset's source and metadata. The variant records the algorithm
+/// that produced it, so switching algorithms reads as a change.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Checksum {
+    Crc32(u32),
+    #[cfg(feature = "blake3")]
+    Blake3([u8; 32]),
+}
+
+impl Checksum {
+    pub fn tag(&self) -> u8 {
+        match self {
+            Checksum::Crc32(_) => 0,
+            #[cfg(feature = "blake3")]
+            Checksum::Blake3(_) => 1,
+        }
+    }
+
+    /// The encoded length, tag included, of a checksum with the given tag.
+    pub fn encoded_len(tag: u8) -> Option<usize> {
+        match tag {
+            0 => Some(5),
+            #[cfg(feature = "blake3")]
+            1 => Some(33),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Checksum {
+    fn default() -> Self {
+        Checksum::Crc32(0)
+    }
+}
+
+impl IntoBytes for Checksum {
+    fn into_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![self.tag()];
+        match self {
+            Checksum::Crc32(value) => bytes.extend(value.into_bytes()),
+            #[cfg(feature = "blake3")]
+            Checksum::Blake3(digest) => bytes.extend(digest),
+        }
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        match *bytes.first()? {
+            0 => Some(Checksum::Crc32(u32::from_bytes(bytes.get(1..5)?)?)),
+            #[cfg(feature = "blake3")]
+            1 => Some(Checksum::Blake3(bytes.get(1..33)?.try_into().ok()?)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct ArtifactMeta {
     id: AssetId,
     ty: AssetType,
-    checksum: u32,
+    settings: SettingsType,
+    checksum: Checksum,
     modified: u64,
     dependencies: HashSet<AssetId>,
 }
@@ -14,28 +79,31 @@ impl ArtifactMeta {
     pub fn new(
         id: AssetId,
         ty: AssetType,
-        checksum: u32,
+        settings: SettingsType,
+        checksum: Checksum,
         modified: u64,
         dependencies: HashSet<AssetId>,
     ) -> Self {
         ArtifactMeta {
             id,
             ty,
+            settings,
             checksum,
             modified,
             dependencies,
         }
     }
 
-    pub fn from<A: Asset>(
+    pub fn from<A: Asset, S: Settings>(
         id: AssetId,
-        checksum: u32,
+        checksum: Checksum,
         modified: u64,
         dependencies: HashSet<AssetId>,
     ) -> Self {
         ArtifactMeta {
             id,
             ty: AssetType::from::<A>(),
+            settings: SettingsType::from::<S>(),
             checksum,
             modified,
             dependencies,
@@ -50,7 +118,11 @@ impl ArtifactMeta {
         self.ty
     }
 
-    pub fn checksum(&self) -> u32 {
+    pub fn settings(&self) -> SettingsType {
+        self.settings
+    }
+
+    pub fn checksum(&self) -> Checksum {
         self.checksum
     }
 
@@ -76,22 +148,201 @@ impl ArtifactMeta {
 
 impl IntoBytes for ArtifactMeta {
     fn into_bytes(&self) -> Vec<u8> {
-        todo!()
+        let mut bytes = Vec::new();
+        bytes.extend(self.id.into_bytes());
+        bytes.extend(self.ty.into_bytes());
+        bytes.extend(self.settings.into_bytes());
+        bytes.extend(self.modified.into_bytes());
+        bytes.extend(self.checksum.into_bytes());
+        // Sorted so identical metadata always encodes to identical bytes.
+        let mut dependencies = self.dependencies.iter().copied().collect::<Vec<_>>();
+        dependencies.sort();
+        bytes.extend(dependencies.into_bytes());
+        bytes
     }
 
     fn from_bytes(bytes: &[u8]) -> Option<Self> {
-        todo!()
+        if bytes.len() < 49 {
+            return None;
+        }
+
+        let id = AssetId::from_bytes(&bytes[0..8])?;
+        let ty = AssetType::from_bytes(&bytes[8..24])?;
+        let settings = SettingsType::from_bytes(&bytes[24..40])?;
+        let modified = u64::from_bytes(&bytes[40..48])?;
+        let end = 48 + Checksum::encoded_len(bytes[48])?;
+        let checksum = Checksum::from_bytes(bytes.get(48..end)?)?;
+        let dependencies = HashSet::<AssetId>::from_bytes(bytes.get(end..)?)?;
+
+        Some(ArtifactMeta {
+            id,
+            ty,
+            settings,
+            checksum,
+            modified,
+            dependencies,
+        })
+    }
+}
+
+pub const ARTIFACT_MAGIC: &[u8; 4] = b"GOB1";
+pub const ARTIFACT_VERSION: u8 = 5;
+pub const ARTIFACT_HEADER_LEN: usize = ARTIFACT_MAGIC.len() + 1;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ArtifactCompression {
+    #[default]
+    None = 0,
+    Deflate = 1,
+}
+
+impl ArtifactCompression {
+    pub fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            0 => Some(ArtifactCompression::None),
+            1 => Some(ArtifactCompression::Deflate),
+            _ => None,
+        }
+    }
+
+    pub fn tag(&self) -> u8 {
+        *self as u8
+    }
+
+    fn compress(&self, bytes: &[u8]) -> Vec<u8> {
+        match self {
+            ArtifactCompression::None => bytes.to_vec(),
+            ArtifactCompression::Deflate => {
+                let mut encoder =
+                    flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(bytes).unwrap();
+                encoder.finish().unwrap()
+            }
+        }
+    }
+
+    fn decompress(&self, bytes: &[u8]) -> Option<Vec<u8>> {
+        match self {
+            ArtifactCompression::None => Some(bytes.to_vec()),
+            ArtifactCompression::Deflate => {
+                let mut asset = Vec::new();
+                flate2::read::DeflateDecoder::new(bytes)
+                    .read_to_end(&mut asset)
+                    .ok()?;
+                Some(asset)
+            }
+        }
+    }
+
+    fn decoder(&self, reader: FileReader) -> FileReader {
+        match self {
+            ArtifactCompression::None => reader,
+            ArtifactCompression::Deflate => {
+                let path = reader.path().map(Path::to_path_buf);
+                let decoder = FileReader::new(flate2::read::DeflateDecoder::new(reader));
+                match path {
+                    Some(path) => decoder.with_path(path),
+                    None => decoder,
+                }
+            }
+        }
+    }
+}
+
+/// An artifact whose asset bytes are read on demand. The reader yields the
+/// decompressed payload.
+pub struct ArtifactReader {
+    meta: ArtifactMeta,
+    compression: ArtifactCompression,
+    payload_checksum: u32,
+    reader: FileReader,
+}
+
+impl ArtifactReader {
+    pub fn new(
+        meta: ArtifactMeta,
+        compression: ArtifactCompression,
+        payload_checksum: u32,
+        reader: FileReader,
+    ) -> Self {
+        Self {
+            meta,
+            compression,
+            payload_checksum,
+            reader: compression.decoder(reader),
+        }
+    }
+
+    pub fn meta(&self) -> &ArtifactMeta {
+        &self.meta
+    }
+
+    pub fn compression(&self) -> ArtifactCompression {
+        self.compression
+    }
+
+    pub fn payload_checksum(&self) -> u32 {
+        self.payload_checksum
+    }
+
+    pub fn reader(&mut self) -> &mut FileReader {
+        &mut self.reader
+    }
+
+    pub fn into_reader(self) -> FileReader {
+        self.reader
+    }
+
+    /// Reads the payload into an eagerly loaded artifact, failing if it doesn't match
+    /// the recorded checksum. Call this before taking any bytes from [`Self::reader`].
+    pub fn into_artifact(mut self) -> Result<Artifact, AssetIoError> {
+        let asset = self.reader.read_to_end()?;
+        let artifact = Artifact {
+            meta: self.meta,
+            asset,
+            compression: self.compression,
+            payload_checksum: self.payload_checksum,
+        };
+
+        match artifact.verify_checksum() {
+            true => Ok(artifact),
+            false => {
+                let path = self.reader.path().unwrap_or(Path::new(""));
+                Err(AssetIoError::ChecksumMismatch(path.to_path_buf()))
+            }
+        }
     }
 }
 
 pub struct Artifact {
     pub meta: ArtifactMeta,
     asset: Vec<u8>,
+    compression: ArtifactCompression,
+    payload_checksum: u32,
 }
 
 impl Artifact {
     pub fn new(meta: ArtifactMeta, asset: Vec<u8>) -> Self {
-        Artifact { meta, asset }
+        let payload_checksum = crc32fast::hash(&asset);
+        Artifact {
+            meta,
+            asset,
+            compression: ArtifactCompression::None,
+            payload_checksum,
+        }
+    }
+
+    pub fn with_compression(mut self, compression: ArtifactCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    pub fn compression(&self) -> ArtifactCompression {
+        self.compression
+    }
+
+    pub fn set_compression(&mut self, compression: ArtifactCompression) {
+        self.compression = compression;
     }
 
     pub fn meta(&self) -> &ArtifactMeta {
@@ -106,24 +357,158 @@ impl Artifact {
         &mut self.meta
     }
 
-    pub fn read_meta(path: &Path) -> std::io::Result<ArtifactMeta> {
-        let mut file = std::fs::File::open(path)?;
+    /// The CRC32 of the asset bytes recorded when the artifact was written. This is
+    /// separate from [`ArtifactMeta::checksum`], which tracks the source file.
+    pub fn payload_checksum(&self) -> u32 {
+        self.payload_checksum
+    }
+
+    /// Recomputes the CRC32 of the asset bytes and compares it to the recorded one.
+    pub fn verify_checksum(&self) -> bool {
+        crc32fast::hash(&self.asset) == self.payload_checksum
+    }
+
+    pub fn is_valid_header(bytes: &[u8]) -> bool {
+        bytes.get(..ARTIFACT_MAGIC.len()) == Some(&ARTIFACT_MAGIC[..])
+            && bytes.get(ARTIFACT_MAGIC.len()) == Some(&ARTIFACT_VERSION)
+    }
+
+    pub fn read_meta(path: &Path) -> Result<ArtifactMeta, AssetIoError> {
+        let mut file = std::fs::File::open(path).map_err(|e| AssetIoError::from_io(e, path))?;
+        let mut header = [0u8; ARTIFACT_HEADER_LEN];
+        if file.read_exact(&mut header).is_err() || !Self::is_valid_header(&header) {
+            return Err(AssetIoError::InvalidHeader(path.to_path_buf()));
+        }
+
+        let invalid =
+            || AssetIoError::InvalidData(format!("Invalid artifact metadata: {:?}", path));
         let mut buffer = [0u8; 8];
-        file.read(&mut buffer)?;
-        let len = usize::from_bytes(&mut buffer)
-            .ok_or::<std::io::Error>(std::io::ErrorKind::InvalidData.into())?;
+        file.read_exact(&mut buffer)
+            .map_err(|e| AssetIoError::from_io(e, path))?;
+        let size = file
+            .metadata()
+            .map_err(|e| AssetIoError::from_io(e, path))?
+            .len();
+        let len = usize::from_bytes(&buffer)
+            .filter(|len| (*len as u64) <= size.saturating_sub(ARTIFACT_HEADER_LEN as u64 + 8))
+            .ok_or_else(invalid)?;
         let mut bytes = vec![0u8; len];
-        file.read_exact(&mut bytes)?;
-        ArtifactMeta::from_bytes(&bytes).ok_or(std::io::ErrorKind::InvalidData.into())
+        file.read_exact(&mut bytes)
+            .map_err(|e| AssetIoError::from_io(e, path))?;
+        ArtifactMeta::from_bytes(&bytes).ok_or_else(invalid)
     }
 }
 
 impl IntoBytes for Artifact {
     fn into_bytes(&self) -> Vec<u8> {
-        todo!()
+        let meta = self.meta.into_bytes();
+        let asset = self.compression.compress(&self.asset);
+        let mut bytes = Vec::with_capacity(ARTIFACT_HEADER_LEN + 13 + meta.len() + asset.len());
+        bytes.extend(ARTIFACT_MAGIC);
+        bytes.push(ARTIFACT_VERSION);
+        bytes.extend(meta.len().into_bytes());
+        bytes.extend(meta);
+        bytes.push(self.compression.tag());
+        bytes.extend(self.payload_checksum.into_bytes());
+        bytes.extend(asset);
+        bytes
     }
 
     fn from_bytes(bytes: &[u8]) -> Option<Self> {
-        todo!()
+        if !Self::is_valid_header(bytes) {
+            return None;
+        }
+
+        let bytes = &bytes[ARTIFACT_HEADER_LEN..];
+        let len = usize::from_bytes(bytes.get(0..8)?)?;
+        let end = len.checked_add(8)?;
+        let meta = ArtifactMeta::from_bytes(bytes.get(8..end)?)?;
+        let compression = ArtifactCompression::from_tag(*bytes.get(end)?)?;
+        let payload_checksum = u32::from_bytes(bytes.get(end + 1..end + 5)?)?;
+        let asset = compression.decompress(&bytes[end + 5..])?;
+
+        Some(Artifact {
+            meta,
+            asset,
+            compression,
+            payload_checksum,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta() -> ArtifactMeta {
+        let dependencies = (0..3).map(|_| AssetId::gen()).collect::<HashSet<_>>();
+        ArtifactMeta::new(
+            AssetId::gen(),
+            AssetType::dynamic(7),
+            SettingsType::dynamic(9),
+            Checksum::Crc32(0xdead_beef),
+            1_700_000_000,
+            dependencies,
+        )
+    }
+
+    #[test]
+    fn artifact_meta_round_trips() {
+        let meta = meta();
+        assert_eq!(ArtifactMeta::from_bytes(&meta.into_bytes()), Some(meta));
+    }
+
+    #[test]
+    fn truncated_artifact_meta_is_rejected() {
+        let bytes = meta().into_bytes();
+        for len in 0..bytes.len() {
+            assert_eq!(ArtifactMeta::from_bytes(&bytes[..len]), None);
+        }
+    }
+
+    #[test]
+    fn artifact_round_trips() {
+        let asset = (0..4096).map(|i| (i * 31 % 251) as u8).collect::<Vec<_>>();
+        let artifact = Artifact::new(meta(), asset.clone());
+        let decoded = Artifact::from_bytes(&artifact.into_bytes()).unwrap();
+
+        assert_eq!(decoded.meta(), artifact.meta());
+        assert_eq!(decoded.asset(), asset);
+        assert!(decoded.verify_checksum());
+    }
+
+    #[test]
+    fn compressed_artifact_round_trips_smaller() {
+        let asset = vec![b'a'; 16 * 1024];
+        let raw = Artifact::new(meta(), asset.clone());
+        let compressed = Artifact::new(raw.meta().clone(), asset.clone())
+            .with_compression(ArtifactCompression::Deflate);
+
+        let bytes = compressed.into_bytes();
+        assert!(bytes.len() < raw.into_bytes().len() / 10);
+
+        let decoded = Artifact::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.compression(), ArtifactCompression::Deflate);
+        assert_eq!(decoded.asset(), asset);
+        assert!(decoded.verify_checksum());
+    }
+
+    #[test]
+    fn dependency_order_does_not_change_bytes() {
+        let ids = (0..32).map(|_| AssetId::gen()).collect::<Vec<_>>();
+        let meta = |dependencies: HashSet<AssetId>| {
+            ArtifactMeta::new(
+                AssetId::default(),
+                AssetType::dynamic(1),
+                SettingsType::dynamic(2),
+                Checksum::Crc32(3),
+                4,
+                dependencies,
+            )
+        };
+
+        let forward = meta(ids.iter().copied().collect());
+        let reverse = meta(ids.iter().rev().copied().collect());
+        assert_eq!(forward.into_bytes(), reverse.into_bytes());
     }
 }
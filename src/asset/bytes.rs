@@ -1,5 +1,7 @@
 use std::{collections::HashSet, hash::Hash};
 
+pub use gob_derive::IntoBytes;
+
 pub trait IntoBytes: Sized {
     fn into_bytes(&self) -> Vec<u8>;
     fn from_bytes(bytes: &[u8]) -> Option<Self>;
@@ -12,7 +14,7 @@ impl IntoBytes for usize {
 
     fn from_bytes(bytes: &[u8]) -> Option<Self> {
         let mut buf = [0; 8];
-        buf.copy_from_slice(&bytes);
+        buf.copy_from_slice(bytes.get(0..8)?);
         Some(usize::from_le_bytes(buf))
     }
 }
@@ -24,11 +26,84 @@ impl IntoBytes for u64 {
 
     fn from_bytes(bytes: &[u8]) -> Option<Self> {
         let mut buf = [0; 8];
-        buf.copy_from_slice(&bytes);
+        buf.copy_from_slice(bytes.get(0..8)?);
         Some(u64::from_le_bytes(buf))
     }
 }
 
+impl IntoBytes for u128 {
+    fn into_bytes(&self) -> Vec<u8> {
+        self.to_le_bytes().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut buf = [0; 16];
+        buf.copy_from_slice(bytes.get(0..16)?);
+        Some(u128::from_le_bytes(buf))
+    }
+}
+
+impl IntoBytes for u32 {
+    fn into_bytes(&self) -> Vec<u8> {
+        self.to_le_bytes().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut buf = [0; 4];
+        buf.copy_from_slice(bytes.get(0..4)?);
+        Some(u32::from_le_bytes(buf))
+    }
+}
+
+impl IntoBytes for u16 {
+    fn into_bytes(&self) -> Vec<u8> {
+        self.to_le_bytes().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut buf = [0; 2];
+        buf.copy_from_slice(bytes.get(0..2)?);
+        Some(u16::from_le_bytes(buf))
+    }
+}
+
+impl IntoBytes for u8 {
+    fn into_bytes(&self) -> Vec<u8> {
+        self.to_le_bytes().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut buf = [0; 1];
+        buf.copy_from_slice(bytes.get(0..1)?);
+        Some(u8::from_le_bytes(buf))
+    }
+}
+
+impl<const N: usize> IntoBytes for [u8; N] {
+    fn into_bytes(&self) -> Vec<u8> {
+        self.to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        bytes.try_into().ok()
+    }
+}
+
+impl IntoBytes for String {
+    fn into_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(8 + self.len());
+        bytes.extend(self.len().into_bytes());
+        bytes.extend(self.as_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let len = usize::from_bytes(bytes.get(0..8)?)?;
+        let bytes = bytes.get(8..)?.get(..len)?;
+        String::from_utf8(bytes.to_vec()).ok()
+    }
+}
+
 impl<I: IntoBytes + Eq + Hash> IntoBytes for HashSet<I> {
     fn into_bytes(&self) -> Vec<u8> {
         let mut bytes = Vec::new();
@@ -44,15 +119,123 @@ impl<I: IntoBytes + Eq + Hash> IntoBytes for HashSet<I> {
     fn from_bytes(bytes: &[u8]) -> Option<Self> {
         let mut set = HashSet::new();
         let mut bytes = bytes;
-        let len = usize::from_bytes(&bytes[0..8])?;
+        let len = usize::from_bytes(bytes.get(0..8)?)?;
         bytes = &bytes[8..];
         for _ in 0..len {
-            let item_len = usize::from_bytes(&bytes[0..8])?;
+            let item_len = usize::from_bytes(bytes.get(0..8)?)?;
             bytes = &bytes[8..];
-            let item = I::from_bytes(&bytes[0..item_len])?;
+            let item = I::from_bytes(bytes.get(0..item_len)?)?;
             bytes = &bytes[item_len..];
             set.insert(item);
         }
         Some(set)
     }
 }
+
+impl<T: IntoBytes> IntoBytes for Vec<T> {
+    fn into_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend(self.len().into_bytes());
+        for item in self {
+            let item_bytes = item.into_bytes();
+            bytes.extend(item_bytes.len().into_bytes());
+            bytes.extend(item_bytes);
+        }
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut bytes = bytes;
+        let len = usize::from_bytes(bytes.get(0..8)?)?;
+        bytes = &bytes[8..];
+        let mut vec = Vec::new();
+        for _ in 0..len {
+            let item_len = usize::from_bytes(bytes.get(0..8)?)?;
+            bytes = &bytes[8..];
+            let item = T::from_bytes(bytes.get(0..item_len)?)?;
+            bytes = &bytes[item_len..];
+            vec.push(item);
+        }
+        Some(vec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset::AssetId;
+
+    #[test]
+    fn small_integers_round_trip() {
+        assert_eq!(
+            u32::from_bytes(&0x1234_5678u32.into_bytes()),
+            Some(0x1234_5678)
+        );
+        assert_eq!(u16::from_bytes(&0xbeefu16.into_bytes()), Some(0xbeef));
+        assert_eq!(u8::from_bytes(&0xabu8.into_bytes()), Some(0xab));
+        assert_eq!(0x0102u16.into_bytes(), [0x02, 0x01]);
+    }
+
+    #[test]
+    fn small_integers_reject_short_slices() {
+        assert_eq!(u32::from_bytes(&[1, 2, 3]), None);
+        assert_eq!(u16::from_bytes(&[1]), None);
+        assert_eq!(u8::from_bytes(&[]), None);
+    }
+
+    #[test]
+    fn short_slices_decode_to_none() {
+        assert_eq!(u64::from_bytes(&[1, 2, 3]), None);
+        assert_eq!(HashSet::<AssetId>::from_bytes(&[1, 2, 3]), None);
+    }
+
+    #[test]
+    fn truncated_sets_decode_to_none() {
+        let set = (0..4).map(|_| AssetId::gen()).collect::<HashSet<_>>();
+        let bytes = set.into_bytes();
+        assert_eq!(HashSet::<AssetId>::from_bytes(&bytes), Some(set));
+        assert_eq!(
+            HashSet::<AssetId>::from_bytes(&bytes[..bytes.len() - 1]),
+            None
+        );
+    }
+
+    #[test]
+    fn strings_round_trip() {
+        let text = String::from("gob 🦀 größe");
+        assert_eq!(
+            String::from_bytes(&IntoBytes::into_bytes(&text)),
+            Some(text)
+        );
+        assert_eq!(
+            String::from_bytes(&IntoBytes::into_bytes(&String::new())),
+            Some(String::new())
+        );
+    }
+
+    #[test]
+    fn invalid_utf8_is_rejected() {
+        let mut bytes = 2usize.into_bytes();
+        bytes.extend([0xff, 0xfe]);
+        assert_eq!(String::from_bytes(&bytes), None);
+    }
+
+    #[test]
+    fn vecs_round_trip() {
+        let values = vec![1u64, u64::MAX, 0, 42];
+        assert_eq!(Vec::<u64>::from_bytes(&values.into_bytes()), Some(values));
+        assert_eq!(
+            Vec::<u64>::from_bytes(&Vec::<u64>::new().into_bytes()),
+            Some(vec![])
+        );
+    }
+
+    #[test]
+    fn byte_arrays_round_trip() {
+        let short: [u8; 16] = std::array::from_fn(|i| i as u8);
+        let long: [u8; 32] = std::array::from_fn(|i| 255 - i as u8);
+        assert_eq!(<[u8; 16]>::from_bytes(&short.into_bytes()), Some(short));
+        assert_eq!(<[u8; 32]>::from_bytes(&long.into_bytes()), Some(long));
+        assert_eq!(<[u8; 32]>::from_bytes(&short.into_bytes()), None);
+    }
+}
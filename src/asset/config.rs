@@ -1,24 +1,83 @@
-use super::{AssetId, PathExt};
+use super::{artifact::Checksum, AssetId, PathExt};
 use std::path::{Path, PathBuf};
 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MetadataFormat {
+    #[default]
+    Toml,
+    Json,
+    Ron,
+}
+
+impl MetadataFormat {
+    pub fn serialize<T: serde::Serialize>(&self, value: &T) -> Result<String, String> {
+        match self {
+            MetadataFormat::Toml => toml::to_string(value).map_err(|e| e.to_string()),
+            MetadataFormat::Json => serde_json::to_string_pretty(value).map_err(|e| e.to_string()),
+            MetadataFormat::Ron => {
+                ron::ser::to_string_pretty(value, Default::default()).map_err(|e| e.to_string())
+            }
+        }
+    }
+
+    pub fn deserialize<T: for<'a> serde::Deserialize<'a>>(
+        &self,
+        content: &str,
+    ) -> Result<T, String> {
+        match self {
+            MetadataFormat::Toml => toml::from_str(content).map_err(|e| e.to_string()),
+            MetadataFormat::Json => serde_json::from_str(content).map_err(|e| e.to_string()),
+            MetadataFormat::Ron => ron::from_str(content).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+/// The algorithm used to hash asset sources and metadata for change detection.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    #[default]
+    Crc32,
+    #[cfg(feature = "blake3")]
+    Blake3,
+}
+
+impl HashAlgorithm {
+    pub fn hash(&self, parts: &[&[u8]]) -> Checksum {
+        match self {
+            HashAlgorithm::Crc32 => {
+                let mut hasher = crc32fast::Hasher::new();
+                parts.iter().for_each(|part| hasher.update(part));
+                Checksum::Crc32(hasher.finalize())
+            }
+            #[cfg(feature = "blake3")]
+            HashAlgorithm::Blake3 => {
+                let mut hasher = blake3::Hasher::new();
+                for part in parts {
+                    hasher.update(part);
+                }
+                Checksum::Blake3(*hasher.finalize().as_bytes())
+            }
+        }
+    }
+}
+
 pub struct AssetConfig {
     root: PathBuf,
     assets: PathBuf,
     preferences: PathBuf,
     cache: PathBuf,
     temp: PathBuf,
+    metadata_format: MetadataFormat,
+    hash_algorithm: HashAlgorithm,
 }
 
 impl AssetConfig {
     pub fn new(root: impl AsRef<Path>) -> Self {
-        let root = root.as_ref().to_path_buf();
-        Self {
-            assets: root.join("Assets"),
-            preferences: root.join("Preferences"),
-            cache: root.join(".cache"),
-            temp: root.join(".temp"),
-            root,
-        }
+        Self::builder(root).build()
+    }
+
+    pub fn builder(root: impl AsRef<Path>) -> AssetConfigBuilder {
+        AssetConfigBuilder::new(root)
     }
 
     pub fn root(&self) -> &Path {
@@ -41,15 +100,35 @@ impl AssetConfig {
         &self.temp
     }
 
+    pub fn metadata_format(&self) -> MetadataFormat {
+        self.metadata_format
+    }
+
+    pub fn hash_algorithm(&self) -> HashAlgorithm {
+        self.hash_algorithm
+    }
+
     pub fn library(&self) -> PathBuf {
         self.cache.join("assets.lib")
     }
 
+    pub fn ignore(&self) -> PathBuf {
+        self.assets.join(".gobignore")
+    }
+
     pub fn artifacts(&self) -> PathBuf {
         self.cache.join("artifacts")
     }
 
+    /// Artifacts are sharded into subdirectories named after the first two hex
+    /// characters of their id to keep directory sizes manageable.
     pub fn artifact(&self, id: &AssetId) -> PathBuf {
+        let id = id.to_string();
+        self.artifacts().join(&id[..2]).join(id)
+    }
+
+    /// The unsharded location used before artifacts were split into subdirectories.
+    pub fn legacy_artifact(&self, id: &AssetId) -> PathBuf {
         self.artifacts().join(id.to_string())
     }
 
@@ -57,3 +136,145 @@ impl AssetConfig {
         path.as_ref().append_extension("meta")
     }
 }
+
+pub struct AssetConfigBuilder {
+    root: PathBuf,
+    assets: PathBuf,
+    preferences: PathBuf,
+    cache: PathBuf,
+    temp: PathBuf,
+    metadata_format: MetadataFormat,
+    hash_algorithm: HashAlgorithm,
+}
+
+impl AssetConfigBuilder {
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+            assets: PathBuf::from("Assets"),
+            preferences: PathBuf::from("Preferences"),
+            cache: PathBuf::from(".cache"),
+            temp: PathBuf::from(".temp"),
+            metadata_format: MetadataFormat::default(),
+            hash_algorithm: HashAlgorithm::default(),
+        }
+    }
+
+    pub fn assets_dir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.assets = dir.as_ref().to_path_buf();
+        self
+    }
+
+    pub fn preferences_dir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.preferences = dir.as_ref().to_path_buf();
+        self
+    }
+
+    pub fn cache_dir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.cache = dir.as_ref().to_path_buf();
+        self
+    }
+
+    pub fn temp_dir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.temp = dir.as_ref().to_path_buf();
+        self
+    }
+
+    pub fn metadata_format(mut self, format: MetadataFormat) -> Self {
+        self.metadata_format = format;
+        self
+    }
+
+    pub fn hash_algorithm(mut self, algorithm: HashAlgorithm) -> Self {
+        self.hash_algorithm = algorithm;
+        self
+    }
+
+    /// Builds the config, resolving each directory relative to the root.
+    pub fn build(self) -> AssetConfig {
+        AssetConfig {
+            assets: self.root.join(self.assets),
+            preferences: self.root.join(self.preferences),
+            cache: self.root.join(self.cache),
+            temp: self.root.join(self.temp),
+            root: self.root,
+            metadata_format: self.metadata_format,
+            hash_algorithm: self.hash_algorithm,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn builder_uses_custom_directory_names() {
+        let config = AssetConfig::builder("project")
+            .assets_dir("content")
+            .cache_dir("build/cache")
+            .temp_dir("scratch")
+            .preferences_dir("settings")
+            .build();
+        let id = AssetId::from_path(Path::new("a.txt"));
+
+        assert_eq!(config.assets(), Path::new("project/content"));
+        assert_eq!(config.temp(), Path::new("project/scratch"));
+        assert_eq!(config.preferences(), Path::new("project/settings"));
+        assert_eq!(
+            config.library(),
+            Path::new("project/build/cache/assets.lib")
+        );
+        assert!(config
+            .artifact(&id)
+            .starts_with("project/build/cache/artifacts"));
+
+        let default = AssetConfig::new("project");
+        assert_eq!(default.assets(), Path::new("project/Assets"));
+        assert_eq!(default.library(), Path::new("project/.cache/assets.lib"));
+    }
+
+    #[test]
+    fn artifacts_are_sharded_by_id_prefix() {
+        let config = AssetConfig::new("project");
+        let first = AssetId::from_str("ab00000000000001").unwrap();
+        let second = AssetId::from_str("cd00000000000002").unwrap();
+        let artifacts = config.artifacts();
+
+        assert_eq!(
+            config.artifact(&first),
+            artifacts.join("ab").join("ab00000000000001")
+        );
+        assert_eq!(
+            config.artifact(&second),
+            artifacts.join("cd").join("cd00000000000002")
+        );
+        assert_eq!(
+            config.legacy_artifact(&first),
+            artifacts.join("ab00000000000001")
+        );
+    }
+
+    #[test]
+    fn hashes_depend_only_on_content() {
+        let algorithms = [
+            HashAlgorithm::Crc32,
+            #[cfg(feature = "blake3")]
+            HashAlgorithm::Blake3,
+        ];
+
+        for algorithm in algorithms {
+            let hash = algorithm.hash(&[b"asset", b"meta"]);
+            assert_eq!(algorithm.hash(&[b"asset", b"meta"]), hash);
+            assert_eq!(algorithm.hash(&[b"assetmeta"]), hash);
+            assert_ne!(algorithm.hash(&[b"asset", b"metb"]), hash);
+        }
+
+        #[cfg(feature = "blake3")]
+        assert_ne!(
+            HashAlgorithm::Crc32.hash(&[b"asset"]),
+            HashAlgorithm::Blake3.hash(&[b"asset"])
+        );
+    }
+}
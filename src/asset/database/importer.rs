@@ -1,9 +1,9 @@
 use crate::{
     asset::{
         artifact::{Artifact, ArtifactMeta},
-        bytes::IntoBytes,
+        config::AssetConfig,
         io::{AssetFileSystem, AssetIoError},
-        Asset, AssetId, AssetMetadata, AssetType, PathExt, Settings,
+        Asset, AssetId, AssetMetadata, AssetType, PathExt, Settings, SettingsType,
     },
     blob::BlobCell,
     dense::DenseMap,
@@ -11,14 +11,32 @@ use crate::{
 use std::{
     collections::{HashMap, HashSet},
     error::Error,
+    marker::PhantomData,
     path::{Path, PathBuf},
+    sync::Arc,
 };
 
 use super::AssetDatabase;
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportErrorKind {
+    MissingExtension,
+    NoImporter { ext: String },
+    ImporterFailed,
+    ProcessorFailed,
+    SaveFailed,
+    IoError,
+    Panicked,
+    Cancelled,
+    NotFound,
+    TypeMismatch,
+}
+
+#[derive(Debug)]
 pub struct ImportError {
     pub path: PathBuf,
     pub id: AssetId,
+    pub kind: ImportErrorKind,
     pub artifact: Option<ArtifactMeta>,
     pub error: Box<dyn Error + Send + Sync>,
 }
@@ -27,16 +45,22 @@ impl ImportError {
     pub fn new<E: Error + Send + Sync + 'static>(
         path: impl AsRef<Path>,
         id: AssetId,
+        kind: ImportErrorKind,
         error: E,
     ) -> Self {
         ImportError {
             path: path.as_ref().to_path_buf(),
             id,
+            kind,
             error: Box::new(error),
             artifact: None,
         }
     }
 
+    pub fn kind(&self) -> &ImportErrorKind {
+        &self.kind
+    }
+
     pub fn with_artifact(mut self, artifact: ArtifactMeta) -> Self {
         self.artifact = Some(artifact);
         self
@@ -48,7 +72,56 @@ impl ImportError {
     }
 }
 
+impl std::fmt::Display for ImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Failed to import {:?}: {}", self.path, self.error)
+    }
+}
+
+impl Error for ImportError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.error.as_ref())
+    }
+}
+
+#[derive(Default)]
+pub struct ImportReport {
+    dependents: HashSet<AssetId>,
+    errors: Vec<ImportError>,
+}
+
+impl ImportReport {
+    pub fn new() -> Self {
+        ImportReport {
+            dependents: HashSet::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn dependents(&self) -> &HashSet<AssetId> {
+        &self.dependents
+    }
+
+    pub fn errors(&self) -> &[ImportError] {
+        &self.errors
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn extend(&mut self, report: ImportReport) {
+        self.dependents.extend(report.dependents);
+        self.errors.extend(report.errors);
+    }
+
+    pub fn take_errors(&mut self) -> Vec<ImportError> {
+        std::mem::take(&mut self.errors)
+    }
+}
+
 pub struct LoadContext<'a, S: Settings> {
+    fs: &'a AssetFileSystem,
     path: &'a Path,
     bytes: &'a [u8],
     metadata: &'a AssetMetadata<S>,
@@ -56,8 +129,14 @@ pub struct LoadContext<'a, S: Settings> {
 }
 
 impl<'a, S: Settings> LoadContext<'a, S> {
-    pub fn new(path: &'a Path, bytes: &'a [u8], metadata: &'a AssetMetadata<S>) -> Self {
+    pub fn new(
+        fs: &'a AssetFileSystem,
+        path: &'a Path,
+        bytes: &'a [u8],
+        metadata: &'a AssetMetadata<S>,
+    ) -> Self {
         LoadContext {
+            fs,
             path,
             bytes,
             metadata,
@@ -85,6 +164,22 @@ impl<'a, S: Settings> LoadContext<'a, S> {
         self.dependencies.insert(id);
     }
 
+    /// Reads another asset's source and records it as a dependency. Relative paths
+    /// are resolved against the directory of the asset being imported.
+    pub fn load_dependency_bytes(
+        &mut self,
+        path: impl AsRef<Path>,
+    ) -> Result<Vec<u8>, AssetIoError> {
+        let path = match self.path.parent() {
+            Some(dir) if path.as_ref().is_relative() => dir.join(path),
+            _ => path.as_ref().to_path_buf(),
+        };
+
+        let bytes = self.fs.read(&path)?;
+        self.dependencies.insert(self.fs.asset_id(&path));
+        Ok(bytes)
+    }
+
     pub fn finish(self) -> HashSet<AssetId> {
         self.dependencies
     }
@@ -100,6 +195,11 @@ pub trait AssetImporter: Send + Sync + 'static {
     fn extensions() -> &'static [&'static str] {
         &[]
     }
+
+    /// The name metadata uses to pin an asset to this importer.
+    fn name() -> &'static str {
+        std::any::type_name::<Self>()
+    }
 }
 
 pub struct ProcessContext<'a, S: Settings> {
@@ -146,19 +246,55 @@ pub trait AssetProcessor: Send + Sync + 'static {
 pub trait AssetSaver: Send + Sync + 'static {
     type Asset: Asset;
     type Settings: Settings;
+    type Error: Error + Send + Sync;
+
+    fn save(
+        asset: &Self::Asset,
+        metadata: &AssetMetadata<Self::Settings>,
+    ) -> Result<Vec<u8>, Self::Error>;
+    fn load(bytes: &[u8]) -> Result<Self::Asset, Self::Error>;
+}
 
-    fn save(asset: &Self::Asset, metadata: &AssetMetadata<Self::Settings>) -> Vec<u8>;
-    fn load(bytes: &[u8]) -> Self::Asset;
+pub struct AssetHandle<A: Asset> {
+    id: AssetId,
+    count: Arc<()>,
+    _marker: PhantomData<fn() -> A>,
+}
+
+impl<A: Asset> AssetHandle<A> {
+    fn new(id: AssetId, count: Arc<()>) -> Self {
+        AssetHandle {
+            id,
+            count,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn id(&self) -> AssetId {
+        self.id
+    }
+
+    pub fn count(&self) -> usize {
+        Arc::strong_count(&self.count) - 1
+    }
+}
+
+impl<A: Asset> Clone for AssetHandle<A> {
+    fn clone(&self) -> Self {
+        AssetHandle::new(self.id, Arc::clone(&self.count))
+    }
 }
 
 pub struct AssetStore {
     assets: HashMap<AssetId, LoadedAsset>,
+    counts: HashMap<AssetId, Arc<()>>,
 }
 
 impl AssetStore {
     pub fn new() -> Self {
         AssetStore {
             assets: HashMap::new(),
+            counts: HashMap::new(),
         }
     }
 
@@ -168,22 +304,85 @@ impl AssetStore {
 
     pub fn extend(&mut self, store: AssetStore) {
         self.assets.extend(store.assets);
+        self.counts.extend(store.counts);
     }
 
     pub fn get<A: Asset>(&self, id: AssetId) -> Option<&A> {
-        self.assets.get(&id).map(|cell| cell.asset())
+        self.assets
+            .get(&id)
+            .filter(|asset| asset.is::<A>())
+            .map(|asset| asset.asset())
+    }
+
+    pub fn get_mut<A: Asset>(&mut self, id: AssetId) -> Option<&mut A> {
+        self.assets
+            .get_mut(&id)
+            .filter(|asset| asset.is::<A>())
+            .map(|asset| asset.asset_mut())
+    }
+
+    pub fn iter<A: Asset>(&self) -> impl Iterator<Item = (AssetId, &A)> {
+        let ty = AssetType::from::<A>();
+        self.assets
+            .iter()
+            .filter(move |(_, asset)| asset.meta().ty() == ty)
+            .map(|(id, asset)| (*id, asset.asset::<A>()))
+    }
+
+    pub fn load_handle<A: Asset>(&mut self, id: AssetId) -> Option<AssetHandle<A>> {
+        let asset = self.assets.get(&id)?;
+        if asset.meta().ty() != AssetType::from::<A>() {
+            return None;
+        }
+
+        let count = self.counts.entry(id).or_default();
+        Some(AssetHandle::new(id, Arc::clone(count)))
+    }
+
+    pub fn handle_count(&self, id: &AssetId) -> usize {
+        self.counts
+            .get(id)
+            .map(|count| Arc::strong_count(count) - 1)
+            .unwrap_or_default()
+    }
+
+    pub fn unload(&mut self) -> Vec<AssetId> {
+        let unloaded = self
+            .assets
+            .keys()
+            .filter(|id| self.handle_count(id) == 0)
+            .copied()
+            .collect::<Vec<_>>();
+
+        for id in &unloaded {
+            self.assets.remove(id);
+            self.counts.remove(id);
+        }
+
+        unloaded
     }
 
     pub fn remove(&mut self, id: AssetId) -> Option<LoadedAsset> {
+        self.counts.remove(&id);
         self.assets.remove(&id)
     }
 
+    /// Removes an asset only if it is an `A`, leaving other types in the store.
+    pub fn remove_typed<A: Asset>(&mut self, id: AssetId) -> Option<A> {
+        if !self.assets.get(&id)?.is::<A>() {
+            return None;
+        }
+
+        self.remove(id).map(|asset| asset.take())
+    }
+
     pub fn contains(&self, id: &AssetId) -> bool {
         self.assets.contains_key(id)
     }
 
     pub fn clear(&mut self) {
         self.assets.clear();
+        self.counts.clear();
     }
 }
 
@@ -250,9 +449,21 @@ impl LoadedAsset {
         &self.meta
     }
 
+    pub fn is<A: Asset>(&self) -> bool {
+        self.meta.ty() == AssetType::from::<A>()
+    }
+
     pub fn asset<A: Asset>(&self) -> &A {
         self.asset.value()
     }
+
+    pub fn asset_mut<A: Asset>(&mut self) -> &mut A {
+        self.asset.value_mut()
+    }
+
+    pub fn take<A: Asset>(self) -> A {
+        self.asset.take()
+    }
 }
 
 pub struct SavedAsset {
@@ -291,7 +502,47 @@ impl Into<LoadedAsset> for SavedAsset {
     }
 }
 
+pub type ImportCallback = Box<dyn Fn(&SavedAsset) + Send + Sync>;
+pub type ImportErrorCallback = Box<dyn Fn(&ImportError) + Send + Sync>;
+
+/// Callbacks run as each asset finishes importing. They are called from the
+/// import worker threads, so they must not block for long.
+#[derive(Default)]
+pub struct ImportObserver {
+    on_import: Option<ImportCallback>,
+    on_error: Option<ImportErrorCallback>,
+}
+
+impl ImportObserver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_on_import(&mut self, callback: ImportCallback) {
+        self.on_import = Some(callback);
+    }
+
+    pub fn set_on_error(&mut self, callback: ImportErrorCallback) {
+        self.on_error = Some(callback);
+    }
+
+    pub fn imported(&self, saved: &SavedAsset) {
+        if let Some(on_import) = &self.on_import {
+            on_import(saved);
+        }
+    }
+
+    pub fn failed(&self, error: &ImportError) {
+        if let Some(on_error) = &self.on_error {
+            on_error(error);
+        }
+    }
+}
+
 pub struct ErasedAssetImporter {
+    name: &'static str,
+    ty: AssetType,
+    settings: SettingsType,
     import: fn(&AssetFileSystem, &Path) -> Result<ImportedAsset, ImportError>,
     pub process: Option<fn(&Path, &mut ImportedAsset, &mut AssetStore) -> Result<(), ImportError>>,
     save: fn(&AssetFileSystem, &Path, ImportedAsset) -> Result<SavedAsset, ImportError>,
@@ -301,27 +552,49 @@ pub struct ErasedAssetImporter {
 impl ErasedAssetImporter {
     pub fn new<I: AssetImporter>() -> Self {
         Self {
+            name: I::name(),
+            ty: AssetType::from::<I::Asset>(),
+            settings: SettingsType::from::<I::Settings>(),
             import: |fs, path| {
-                let metadata = fs.load_metadata::<I::Settings>(path).unwrap_or_default();
-                let metabytes = fs
-                    .save_metadata(path, &metadata)
-                    .map_err(|e| ImportError::new(path, metadata.id(), e))?;
-                let bytes = fs
-                    .read(path)
-                    .map_err(|e| ImportError::new(path, metadata.id(), e))?;
+                let metadata = match fs.load_metadata::<I::Settings>(path) {
+                    Ok(metadata) => metadata,
+                    Err(_) if !fs.exists(AssetConfig::metadata(path)) => {
+                        AssetMetadata::new(fs.asset_id(path), Default::default())
+                    }
+                    // Metadata written for other settings keeps its id; metadata whose
+                    // id can't be read is left alone rather than overwritten.
+                    Err(_) => match fs.load_metadata_id(path) {
+                        Ok(id) => AssetMetadata::new(id, Default::default()),
+                        Err(e) => {
+                            let kind = ImportErrorKind::IoError;
+                            return Err(ImportError::new(path, AssetId::default(), kind, e));
+                        }
+                    },
+                };
+                let metabytes = fs.save_metadata(path, &metadata).map_err(|e| {
+                    ImportError::new(path, metadata.id(), ImportErrorKind::IoError, e)
+                })?;
+                let bytes = fs.read(path).map_err(|e| {
+                    ImportError::new(path, metadata.id(), ImportErrorKind::IoError, e)
+                })?;
 
                 let (asset, dependencies) = {
-                    let mut ctx = LoadContext::new(&path, &bytes, &metadata);
-                    let asset = I::import(&mut ctx)
-                        .map_err(|e| ImportError::new(path, metadata.id(), e))?;
+                    let mut ctx = LoadContext::new(fs, &path, &bytes, &metadata);
+                    let asset = I::import(&mut ctx).map_err(|e| {
+                        ImportError::new(path, metadata.id(), ImportErrorKind::ImporterFailed, e)
+                    })?;
                     (asset, ctx.finish())
                 };
 
-                let modified = AssetFileSystem::modified_secs(path).unwrap_or_default();
-                let checksum = AssetFileSystem::calculate_checksum(&bytes, &metabytes);
+                let modified = fs.modified_secs(path).unwrap_or_default();
+                let checksum = fs.calculate_checksum(&bytes, &metabytes);
 
-                let artifact =
-                    ArtifactMeta::from::<I::Asset>(metadata.id(), checksum, modified, dependencies);
+                let artifact = ArtifactMeta::from::<I::Asset, I::Settings>(
+                    metadata.id(),
+                    checksum,
+                    modified,
+                    dependencies,
+                );
 
                 Ok(ImportedAsset::new(asset, metadata, artifact))
             },
@@ -333,12 +606,19 @@ impl ErasedAssetImporter {
                 let metadata = imported.metadata::<I::Settings>();
                 let id = metadata.id();
 
-                let bytes = I::Saver::save(asset, metadata);
+                let bytes = match I::Saver::save(asset, metadata) {
+                    Ok(bytes) => bytes,
+                    Err(e) => {
+                        let error = ImportError::new(path, id, ImportErrorKind::SaveFailed, e)
+                            .with_option_artifact(prev_artifact);
+                        return Err(error);
+                    }
+                };
                 let artifact = Artifact::new(imported.artifact, bytes);
-                let artifact_bytes = artifact.into_bytes();
 
-                if let Err(e) = fs.write(path, artifact_bytes) {
-                    let error = ImportError::new(path, id, e).with_option_artifact(prev_artifact);
+                if let Err(e) = fs.save_artifact(&artifact) {
+                    let error = ImportError::new(path, id, ImportErrorKind::IoError, e)
+                        .with_option_artifact(prev_artifact);
                     return Err(error);
                 }
 
@@ -352,20 +632,42 @@ impl ErasedAssetImporter {
                 Ok(SavedAsset::new(asset, meta, prev_artifact, removed))
             },
             load: |artifact| {
-                let asset = I::Saver::load(artifact.asset());
+                if artifact.meta().settings() != SettingsType::from::<I::Settings>() {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        "Artifact was imported with a different settings type.",
+                    ));
+                }
+
+                let asset = I::Saver::load(artifact.asset())
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
 
                 Ok(LoadedAsset::new(asset, artifact.meta))
             },
         }
     }
 
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn ty(&self) -> AssetType {
+        self.ty
+    }
+
+    pub fn settings(&self) -> SettingsType {
+        self.settings
+    }
+
     pub fn set_processer<P: AssetProcessor>(&mut self) {
         self.process = Some(|path, imported, assets| {
             let (asset, metadata, artifact) = imported.mutate();
 
             let mut ctx = ProcessContext::new(assets, &metadata, artifact.dependencies());
 
-            P::process(asset, &mut ctx).map_err(|e| ImportError::new(path, metadata.id(), e))
+            P::process(asset, &mut ctx).map_err(|e| {
+                ImportError::new(path, metadata.id(), ImportErrorKind::ProcessorFailed, e)
+            })
         });
     }
 
@@ -389,7 +691,7 @@ impl ErasedAssetImporter {
 
 pub struct AssetImporters {
     importers: DenseMap<AssetType, ErasedAssetImporter>,
-    types: HashMap<&'static str, AssetType>,
+    types: HashMap<String, Vec<AssetType>>,
 }
 
 impl AssetImporters {
@@ -406,7 +708,17 @@ impl AssetImporters {
 
         self.importers.insert(ty, importer);
         for ext in I::extensions() {
-            self.types.insert(ext, ty);
+            let types = self.types.entry(ext.to_lowercase()).or_default();
+            if !types.contains(&ty) {
+                types.push(ty);
+            }
+        }
+    }
+
+    pub fn register_processor<P: AssetProcessor>(&mut self) {
+        let ty = AssetType::from::<<P::Importer as AssetImporter>::Asset>();
+        if let Some(importer) = self.importers.get_mut(&ty) {
+            importer.set_processer::<P>();
         }
     }
 
@@ -414,8 +726,33 @@ impl AssetImporters {
         self.importers.get(&ty)
     }
 
+    /// Picks the extension importers are looked up by: the compound extension
+    /// (`tar.gz`) if something is registered for it, otherwise the final one.
+    pub fn path_ext<'a>(&self, path: &'a Path) -> Option<&'a str> {
+        path.full_ext()
+            .filter(|ext| self.types.contains_key(&ext.to_lowercase()))
+            .or_else(|| path.ext())
+    }
+
+    /// Returns the first importer registered for the extension.
     pub fn importer_by_ext(&self, ext: &str) -> Option<&ErasedAssetImporter> {
-        self.types.get(ext).and_then(|ty| self.importer(*ty))
+        self.importers_by_ext(ext).next()
+    }
+
+    pub fn importers_by_ext(&self, ext: &str) -> impl Iterator<Item = &ErasedAssetImporter> {
+        self.types
+            .get(&ext.to_lowercase())
+            .into_iter()
+            .flatten()
+            .filter_map(|ty| self.importer(*ty))
+    }
+
+    /// Importers are searched in registration order, so the first one registered
+    /// under a name wins.
+    pub fn importer_by_name(&self, name: &str) -> Option<&ErasedAssetImporter> {
+        self.importers
+            .values()
+            .find(|importer| importer.name() == name)
     }
 }
 
@@ -472,72 +809,190 @@ fn import_asset(
     fs: &AssetFileSystem,
     db: &AssetDatabase,
     assets: &mut AssetStore,
-) -> Result<SavedAsset, ImportError> {
-    let ext = path.ext().ok_or(ImportError::new(
+    force: bool,
+) -> Result<Option<SavedAsset>, ImportError> {
+    if !force && is_up_to_date(path, fs, db) {
+        return Ok(None);
+    }
+
+    let importers = db.importers();
+    let ext = importers.path_ext(path).ok_or(ImportError::new(
         path,
         AssetId::default(),
+        ImportErrorKind::MissingExtension,
         CustomError::from("No extension."),
     ))?;
 
-    let importers = db.importers();
-    let importer = importers.importer_by_ext(ext).ok_or(ImportError::new(
+    let importer = find_importer(&importers, path, ext, fs).ok_or(ImportError::new(
         path,
         AssetId::default(),
+        ImportErrorKind::NoImporter {
+            ext: ext.to_string(),
+        },
         CustomError::from("No importer found for extension"),
     ))?;
 
     let mut imported = importer.import(fs, path)?;
 
     if let Some(process) = importer.process {
-        load_import_dependencies(imported.artifact.dependencies(), fs, db, assets);
+        load_import_dependencies(imported.artifact.dependencies(), fs, &importers, assets);
         process(path, &mut imported, assets)?;
     }
 
-    importer.save(fs, path, imported)
+    importer.save(fs, path, imported).map(Some)
 }
 
-fn full_import<A: AsRef<Path>>(paths: &[A], fs: &AssetFileSystem, db: &AssetDatabase) {
-    let mut dependents = HashSet::new();
-    dependents.extend(chunked_import(paths, fs, db));
+/// Picks the importer pinned in the asset's metadata, falling back to the first
+/// importer registered for the extension.
+fn find_importer<'a>(
+    importers: &'a AssetImporters,
+    path: &Path,
+    ext: &str,
+    fs: &AssetFileSystem,
+) -> Option<&'a ErasedAssetImporter> {
+    let pinned = fs.load_metadata_importer(path);
+    importers
+        .importers_by_ext(ext)
+        .find(|importer| Some(importer.name()) == pinned.as_deref())
+        .or_else(|| importers.importer_by_ext(ext))
+}
 
-    while !dependents.is_empty() {
-        let paths = dependents
+pub(super) fn is_up_to_date(path: &Path, fs: &AssetFileSystem, db: &AssetDatabase) -> bool {
+    let id = match db.library().path_id(path) {
+        Some(id) => *id,
+        None => return false,
+    };
+
+    let artifact = match fs.load_artifact_meta(&id) {
+        Ok(artifact) => artifact,
+        Err(_) => return false,
+    };
+
+    let importers = db.importers();
+    let importer = match importers
+        .path_ext(path)
+        .and_then(|ext| find_importer(&importers, path, ext, fs))
+    {
+        Some(importer) => importer,
+        None => return false,
+    };
+
+    if artifact.ty() != importer.ty() || artifact.settings() != importer.settings() {
+        return false;
+    }
+
+    let modified = fs.modified_secs(path).unwrap_or_default();
+    if artifact.modified() != modified {
+        return false;
+    }
+
+    let bytes = match fs.read(path) {
+        Ok(bytes) => bytes,
+        Err(_) => return false,
+    };
+
+    let metadata = match fs.read(AssetConfig::metadata(path)) {
+        Ok(metadata) => metadata,
+        Err(_) => return false,
+    };
+
+    fs.calculate_checksum(&bytes, &metadata) == artifact.checksum()
+}
+
+pub(super) fn full_import<A: AsRef<Path>>(
+    paths: &[A],
+    fs: &Arc<AssetFileSystem>,
+    db: &AssetDatabase,
+    force: bool,
+) -> ImportReport {
+    let mut report = chunked_import(paths, fs, db, force);
+    let mut queued = HashSet::new();
+
+    while !report.dependents.is_empty() {
+        let paths = report
+            .dependents
             .drain()
+            .filter(|id| queued.insert(*id))
             .filter_map(|id| db.library().id_path(&id).cloned())
             .collect::<Vec<_>>();
-        dependents.extend(chunked_import(&paths, fs, db));
+        report.extend(chunked_import(&paths, fs, db, true));
     }
+
+    report
 }
 
 fn chunked_import<A: AsRef<Path>>(
     paths: &[A],
-    fs: &AssetFileSystem,
+    fs: &Arc<AssetFileSystem>,
     db: &AssetDatabase,
-) -> impl IntoIterator<Item = AssetId> {
-    let mut dependents = HashSet::new();
+    force: bool,
+) -> ImportReport {
+    let mut report = ImportReport::new();
+    let mut handles = Vec::new();
+    for paths in paths.chunks(250) {
+        // A shut down pool drops new tasks, so import the chunk on this thread instead.
+        if db.pool().is_shutdown() {
+            report.extend(import_assets(paths, fs, db, force));
+            continue;
+        }
 
-    paths.chunks(250).for_each(|paths| {
-        let ids = import_assets(paths, fs, db);
-        dependents.extend(ids);
-    });
+        let paths = paths
+            .iter()
+            .map(|path| path.as_ref().to_path_buf())
+            .collect::<Vec<_>>();
+        let (chunk, fs, task_db) = (paths.clone(), Arc::clone(fs), db.clone());
+        let handle = db
+            .pool()
+            .spawn_with_result(move || import_assets(&chunk, &fs, &task_db, force));
 
-    dependents
+        handles.push((paths, handle));
+    }
+
+    for (paths, handle) in handles {
+        let (kind, message) = match handle.join() {
+            Some(chunk) => {
+                report.extend(chunk);
+                continue;
+            }
+            None if db.pool().is_shutdown() => {
+                (ImportErrorKind::Cancelled, "Import task pool was shut down")
+            }
+            None => (ImportErrorKind::Panicked, "Import task panicked"),
+        };
+
+        report.errors.extend(paths.iter().map(|path| {
+            let id = db.library().path_id(path).copied().unwrap_or_default();
+            ImportError::new(path, id, kind.clone(), CustomError::from(message))
+        }));
+    }
+
+    report
 }
 
 fn import_assets<A: AsRef<Path>>(
     paths: &[A],
     fs: &AssetFileSystem,
     db: &AssetDatabase,
-) -> impl IntoIterator<Item = AssetId> {
+    force: bool,
+) -> ImportReport {
     let mut assets = AssetStore::new();
     let mut dep_updates = HashMap::new();
+    let mut report = ImportReport::new();
+    let mut imported = Vec::new();
 
     for path in paths {
-        let saved = match import_asset(path.as_ref(), fs, db, &mut assets) {
-            Ok(saved) => saved,
-            Err(_) => continue,
+        let saved = match import_asset(path.as_ref(), fs, db, &mut assets, force) {
+            Ok(Some(saved)) => saved,
+            Ok(None) => continue,
+            Err(error) => {
+                db.observer().failed(&error);
+                report.errors.push(error);
+                continue;
+            }
         };
 
+        db.observer().imported(&saved);
+
         for id in saved.meta.dependencies() {
             let updates = dep_updates.entry(*id).or_insert_with(DependentUpdates::new);
             updates.add(saved.meta.id());
@@ -547,28 +1002,43 @@ fn import_assets<A: AsRef<Path>>(
             let updates = dep_updates.entry(id).or_insert_with(DependentUpdates::new);
             updates.remove(saved.meta.id());
         }
+
+        imported.push((saved.meta.id(), path.as_ref().to_path_buf()));
+    }
+
+    let mut library = db.library_mut();
+    for (id, path) in &imported {
+        library.insert(*id, path.clone());
     }
 
-    let mut returned = HashSet::<AssetId>::new();
     for (id, updates) in dep_updates {
-        let _ = update_dependents(id, fs, &updates);
-        returned.extend(updates.added());
+        for dependent in updates.added() {
+            library.add_dependent(id, *dependent);
+        }
+
+        for dependent in updates.removed() {
+            library.remove_dependent(&id, dependent);
+        }
     }
 
-    returned
+    for (id, _) in &imported {
+        if let Some(dependents) = library.dependents(id) {
+            report.dependents.extend(dependents);
+        }
+    }
+
+    report
 }
 
 fn load_import_dependencies<'a>(
     ids: impl IntoIterator<Item = &'a AssetId>,
     fs: &AssetFileSystem,
-    db: &AssetDatabase,
+    importers: &AssetImporters,
     assets: &mut AssetStore,
 ) {
-    let importers = db.importers();
-
     for id in ids {
         if assets.contains(id) {
-            return;
+            continue;
         }
 
         let artifact = match fs.load_artifact(id) {
@@ -588,22 +1058,555 @@ fn load_import_dependencies<'a>(
     }
 }
 
-fn update_dependents(
-    id: AssetId,
-    fs: &AssetFileSystem,
-    updates: &DependentUpdates,
-) -> Result<(), AssetIoError> {
-    let path = fs.config().temp().join("dependents").join(id.to_string());
-    let bytes = fs.read(&path)?;
-
-    let mut dependents = HashSet::<AssetId>::from_bytes(&bytes).unwrap_or_default();
-    dependents.extend(updates.added());
-    dependents.retain(|id| !updates.removed().contains(id));
-
-    if !dependents.is_empty() {
-        let bytes = dependents.into_bytes();
-        fs.write(&path, &bytes)
-    } else {
-        fs.remove(&path).map(|_| ())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset::database::tests::TextImporter;
+    use crate::asset::database::tests::{setup, Text, TextSettings};
+    use std::str::FromStr;
+
+    #[test]
+    fn import_keeps_settings_from_legacy_metadata() {
+        let (fs, db) = setup();
+        let path = fs.config().assets().join("a.txt");
+        fs.write(&path, "hello").unwrap();
+        fs.write(
+            AssetConfig::metadata(&path),
+            "id = 42\n[settings]\nupper = true\n",
+        )
+        .unwrap();
+
+        assert!(db.import_paths(&[&path], &fs).is_ok());
+        let id = AssetId::from_str("000000000000002a").unwrap();
+        assert_eq!(db.library().path_id(&path), Some(&id));
+        let loaded = db.load::<Text>(&id, &fs).unwrap();
+        assert_eq!(loaded.asset::<Text>().0, "HELLO");
+    }
+
+    #[test]
+    fn unchanged_assets_are_not_reimported() {
+        let (fs, db) = setup();
+        let path = fs.config().assets().join("a.txt");
+        fs.write(&path, "hello").unwrap();
+        assert!(db.import_paths(&[&path], &fs).is_ok());
+
+        let id = *db.library().path_id(&path).unwrap();
+        let artifact = fs.config().artifact(&id);
+        let written = fs.file_metadata(&artifact).unwrap().modified();
+        assert!(is_up_to_date(&path, &fs, &db));
+        assert!(db.import_paths(&[&path], &fs).is_ok());
+        assert_eq!(fs.file_metadata(&artifact).unwrap().modified(), written);
+
+        fs.write(&path, "changed").unwrap();
+        assert!(!is_up_to_date(&path, &fs, &db));
+        assert!(db.import_paths(&[&path], &fs).is_ok());
+        assert_ne!(fs.file_metadata(&artifact).unwrap().modified(), written);
+    }
+
+    #[test]
+    fn import_rejects_unreadable_metadata() {
+        let (fs, db) = setup();
+        let path = fs.config().assets().join("a.txt");
+        let metadata = "id = \"not an id\"\n[settings]\nupper = true\n";
+        fs.write(&path, "hello").unwrap();
+        fs.write(AssetConfig::metadata(&path), metadata).unwrap();
+
+        let report = db.import_paths(&[&path], &fs);
+        assert_eq!(report.errors().len(), 1);
+        assert_eq!(report.errors()[0].kind(), &ImportErrorKind::IoError);
+        let content = fs.read_to_string(AssetConfig::metadata(&path)).unwrap();
+        assert_eq!(content, metadata);
+    }
+
+    fn imported_texts(fs: &Arc<AssetFileSystem>, db: &AssetDatabase) -> Vec<(PathBuf, String)> {
+        let paths = db
+            .library()
+            .iter()
+            .map(|(id, path)| (*id, path.clone()))
+            .collect::<Vec<_>>();
+        let mut texts = paths
+            .into_iter()
+            .map(|(id, path)| {
+                (
+                    path,
+                    db.load::<Text>(&id, fs).unwrap().asset::<Text>().0.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        texts.sort();
+        texts
+    }
+
+    #[test]
+    fn chunked_import_matches_sequential_import() {
+        let (fs, db) = setup();
+        let (sequential_fs, sequential_db) = setup();
+        let paths = (0..600)
+            .map(|index| fs.config().assets().join(format!("{}.txt", index)))
+            .collect::<Vec<_>>();
+        for (index, path) in paths.iter().enumerate() {
+            fs.write(path, index.to_string()).unwrap();
+            sequential_fs.write(path, index.to_string()).unwrap();
+        }
+
+        let report = chunked_import(&paths, &fs, &db, false);
+        let sequential = import_assets(&paths, &sequential_fs, &sequential_db, false);
+        assert!(report.is_ok() && sequential.is_ok());
+        assert_eq!(db.library().len(), 600);
+        assert_eq!(
+            imported_texts(&fs, &db),
+            imported_texts(&sequential_fs, &sequential_db)
+        );
+    }
+
+    struct Panics;
+
+    impl Asset for Panics {}
+
+    impl AssetImporter for Panics {
+        type Asset = Panics;
+        type Settings = TextSettings;
+        type Saver = Panics;
+        type Error = std::io::Error;
+
+        fn import(_: &mut LoadContext<Self::Settings>) -> Result<Panics, std::io::Error> {
+            panic!("importer panicked")
+        }
+
+        fn extensions() -> &'static [&'static str] {
+            &["panic"]
+        }
+    }
+
+    impl AssetSaver for Panics {
+        type Asset = Panics;
+        type Settings = TextSettings;
+        type Error = std::io::Error;
+
+        fn save(_: &Panics, _: &AssetMetadata<TextSettings>) -> Result<Vec<u8>, std::io::Error> {
+            Ok(vec![])
+        }
+
+        fn load(_: &[u8]) -> Result<Panics, std::io::Error> {
+            Ok(Panics)
+        }
+    }
+
+    #[test]
+    fn chunked_import_reports_panicked_tasks() {
+        let (fs, db) = setup();
+        db.register_importer::<Panics>();
+        let path = fs.config().assets().join("a.panic");
+        fs.write(&path, "").unwrap();
+
+        let report = db.import_paths(&[&path], &fs);
+        assert_eq!(report.errors().len(), 1);
+        assert_eq!(report.errors()[0].kind(), &ImportErrorKind::Panicked);
+        assert_eq!(report.errors()[0].path, path);
+    }
+
+    #[test]
+    fn chunked_import_runs_inline_after_shutdown() {
+        let (fs, db) = setup();
+        let path = fs.config().assets().join("a.txt");
+        fs.write(&path, "a").unwrap();
+        db.pool().shutdown();
+
+        let report = chunked_import(&[&path], &fs, &db, false);
+        assert!(report.is_ok());
+        assert!(db.library().path_id(&path).is_some());
+    }
+
+    #[test]
+    fn import_reports_files_without_an_importer() {
+        let (fs, db) = setup();
+        let text = fs.config().assets().join("a.txt");
+        let unknown = fs.config().assets().join("b.xyz");
+        fs.write(&text, "a").unwrap();
+        fs.write(&unknown, "b").unwrap();
+
+        let report = import_assets(&[&text, &unknown], &fs, &db, false);
+        assert_eq!(report.errors().len(), 1);
+        assert_eq!(report.errors()[0].path, unknown);
+        assert!(db.library().path_id(&text).is_some());
+    }
+
+    #[test]
+    fn import_errors_display_their_path() {
+        let error = ImportError::new(
+            "assets/broken.txt",
+            AssetId::default(),
+            ImportErrorKind::ImporterFailed,
+            CustomError::from("bad header"),
+        );
+
+        let message = error.to_string();
+        assert!(message.contains("assets/broken.txt"), "{}", message);
+        assert!(message.contains("bad header"), "{}", message);
+        assert!(error.source().unwrap().to_string().ends_with("bad header"));
+    }
+
+    #[test]
+    fn full_import_terminates_on_dependency_cycles() {
+        let (fs, db) = setup();
+        let (a, b) = (
+            fs.config().assets().join("a.txt"),
+            fs.config().assets().join("b.txt"),
+        );
+        fs.write(&a, "a").unwrap();
+        fs.write(&b, "b").unwrap();
+        assert!(db.import_paths(&[&a, &b], &fs).is_ok());
+
+        let a_id = *db.library().path_id(&a).unwrap();
+        let b_id = *db.library().path_id(&b).unwrap();
+        db.library_mut().add_dependent(a_id, b_id);
+        db.library_mut().add_dependent(b_id, a_id);
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let task_db = db.clone();
+        std::thread::spawn(move || {
+            let _ = sender.send(full_import(&[a], &fs, &task_db, true));
+        });
+
+        let report = receiver
+            .recv_timeout(std::time::Duration::from_secs(10))
+            .expect("full_import did not terminate");
+        assert!(report.is_ok());
+    }
+
+    struct Exclaim;
+
+    impl AssetProcessor for Exclaim {
+        type Importer = TextImporter;
+        type Error = std::io::Error;
+
+        fn process(asset: &mut Text, _: &mut ProcessContext<TextSettings>) -> std::io::Result<()> {
+            asset.0.push('!');
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn registered_processors_run_after_import() {
+        let (fs, db) = setup();
+        let importer_ty = AssetType::from::<Text>();
+        assert!(db
+            .importers()
+            .importer(importer_ty)
+            .unwrap()
+            .process
+            .is_none());
+
+        db.register_processor::<Exclaim>();
+        assert!(db
+            .importers()
+            .importer(importer_ty)
+            .unwrap()
+            .process
+            .is_some());
+
+        let path = fs.config().assets().join("a.txt");
+        fs.write(&path, "hi").unwrap();
+        assert!(db.import_paths(&[&path], &fs).is_ok());
+        let id = *db.library().path_id(&path).unwrap();
+        assert_eq!(db.load::<Text>(&id, &fs).unwrap().asset::<Text>().0, "hi!");
+    }
+
+    fn loaded<A: Asset>(asset: A) -> (AssetId, LoadedAsset) {
+        let id = AssetId::gen();
+        let meta = ArtifactMeta::from::<A, TextSettings>(id, Default::default(), 0, HashSet::new());
+        (id, LoadedAsset::new(asset, meta))
+    }
+
+    #[test]
+    fn assets_unload_once_every_handle_drops() {
+        let mut store = AssetStore::new();
+        let (id, asset) = loaded(Text(String::from("a")));
+        store.insert(id, asset);
+
+        let handle = store.load_handle::<Text>(id).unwrap();
+        let clone = handle.clone();
+        assert_eq!(store.handle_count(&id), 2);
+        assert!(store.load_handle::<Panics>(id).is_none());
+        assert!(store.unload().is_empty());
+
+        drop(handle);
+        drop(clone);
+        assert_eq!(store.unload(), [id]);
+        assert!(!store.contains(&id));
+    }
+
+    #[test]
+    fn store_iterates_one_asset_type() {
+        let mut store = AssetStore::new();
+        let mut texts = Vec::new();
+        for text in ["a", "b"] {
+            let (id, asset) = loaded(Text(text.to_string()));
+            store.insert(id, asset);
+            texts.push((id, text.to_string()));
+        }
+        let (id, asset) = loaded(Panics);
+        store.insert(id, asset);
+
+        let mut found = store
+            .iter::<Text>()
+            .map(|(id, text)| (id, text.0.clone()))
+            .collect::<Vec<_>>();
+        found.sort();
+        texts.sort();
+        assert_eq!(found, texts);
+        assert_eq!(store.iter::<Panics>().count(), 1);
+    }
+
+    struct Strict(Vec<u8>);
+
+    impl Asset for Strict {}
+
+    impl AssetImporter for Strict {
+        type Asset = Strict;
+        type Settings = TextSettings;
+        type Saver = Strict;
+        type Error = std::io::Error;
+
+        fn import(ctx: &mut LoadContext<Self::Settings>) -> std::io::Result<Strict> {
+            Ok(Strict(ctx.bytes().to_vec()))
+        }
+
+        fn extensions() -> &'static [&'static str] {
+            &["strict"]
+        }
+    }
+
+    impl AssetSaver for Strict {
+        type Asset = Strict;
+        type Settings = TextSettings;
+        type Error = CustomError;
+
+        fn save(asset: &Strict, _: &AssetMetadata<TextSettings>) -> Result<Vec<u8>, CustomError> {
+            match asset.0.is_empty() {
+                true => Err(CustomError::from("Nothing to save.")),
+                false => Ok(asset.0.clone()),
+            }
+        }
+
+        fn load(bytes: &[u8]) -> Result<Strict, CustomError> {
+            match bytes.is_empty() {
+                true => Err(CustomError::from("Nothing to load.")),
+                false => Ok(Strict(bytes.to_vec())),
+            }
+        }
+    }
+
+    #[test]
+    fn saver_errors_fail_the_import() {
+        let (fs, db) = setup();
+        db.register_importer::<Strict>();
+        let (empty, full) = (
+            fs.config().assets().join("a.strict"),
+            fs.config().assets().join("b.strict"),
+        );
+        fs.write(&empty, "").unwrap();
+        fs.write(&full, "data").unwrap();
+
+        let report = db.import_paths(&[&empty, &full], &fs);
+        assert_eq!(report.errors().len(), 1);
+        assert_eq!(report.errors()[0].path, empty);
+        assert_eq!(report.errors()[0].kind(), &ImportErrorKind::SaveFailed);
+        assert!(Strict::load(&[]).is_err());
+    }
+
+    struct Image;
+
+    impl Asset for Image {}
+
+    impl AssetImporter for Image {
+        type Asset = Image;
+        type Settings = TextSettings;
+        type Saver = Image;
+        type Error = std::io::Error;
+
+        fn import(_: &mut LoadContext<Self::Settings>) -> std::io::Result<Image> {
+            Ok(Image)
+        }
+
+        fn extensions() -> &'static [&'static str] {
+            &["png"]
+        }
+    }
+
+    impl AssetSaver for Image {
+        type Asset = Image;
+        type Settings = TextSettings;
+        type Error = std::io::Error;
+
+        fn save(_: &Image, _: &AssetMetadata<TextSettings>) -> std::io::Result<Vec<u8>> {
+            Ok(vec![])
+        }
+
+        fn load(_: &[u8]) -> std::io::Result<Image> {
+            Ok(Image)
+        }
+    }
+
+    #[test]
+    fn extensions_match_case_insensitively() {
+        let mut importers = AssetImporters::new();
+        importers.register::<Image>();
+
+        for ext in ["png", "PNG", "Png"] {
+            let importer = importers.importer_by_ext(ext).unwrap();
+            assert_eq!(importer.ty(), AssetType::from::<Image>());
+        }
+        assert_eq!(importers.path_ext(Path::new("Texture.PNG")), Some("PNG"));
+    }
+
+    struct Thumbnail;
+
+    impl Asset for Thumbnail {}
+
+    impl AssetImporter for Thumbnail {
+        type Asset = Thumbnail;
+        type Settings = TextSettings;
+        type Saver = Thumbnail;
+        type Error = std::io::Error;
+
+        fn import(_: &mut LoadContext<Self::Settings>) -> std::io::Result<Thumbnail> {
+            Ok(Thumbnail)
+        }
+
+        fn extensions() -> &'static [&'static str] {
+            &["png"]
+        }
+    }
+
+    impl AssetSaver for Thumbnail {
+        type Asset = Thumbnail;
+        type Settings = TextSettings;
+        type Error = std::io::Error;
+
+        fn save(_: &Thumbnail, _: &AssetMetadata<TextSettings>) -> std::io::Result<Vec<u8>> {
+            Ok(vec![])
+        }
+
+        fn load(_: &[u8]) -> std::io::Result<Thumbnail> {
+            Ok(Thumbnail)
+        }
+    }
+
+    #[test]
+    fn extensions_can_have_several_importers() {
+        let (fs, _) = setup();
+        let mut importers = AssetImporters::new();
+        importers.register::<Image>();
+        importers.register::<Thumbnail>();
+
+        let types = importers
+            .importers_by_ext("png")
+            .map(|importer| importer.ty())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            [AssetType::from::<Image>(), AssetType::from::<Thumbnail>()]
+        );
+
+        let path = fs.config().assets().join("icon.png");
+        fs.write(&path, "").unwrap();
+        let importer = find_importer(&importers, &path, "png", &fs).unwrap();
+        assert_eq!(importer.ty(), AssetType::from::<Image>());
+
+        let metadata = AssetMetadata::new(AssetId::gen(), TextSettings::default())
+            .with_importer(<Thumbnail as AssetImporter>::name());
+        fs.save_metadata(&path, &metadata).unwrap();
+        let importer = find_importer(&importers, &path, "png", &fs).unwrap();
+        assert_eq!(importer.ty(), AssetType::from::<Thumbnail>());
+    }
+
+    #[test]
+    fn store_mutates_and_removes_typed_assets() {
+        let mut store = AssetStore::new();
+        let (id, asset) = loaded(Text("hello".into()));
+        store.insert(id, asset);
+
+        store.get_mut::<Text>(id).unwrap().0.push_str(" world");
+        assert_eq!(store.get::<Text>(id).unwrap().0, "hello world");
+        assert!(store.get_mut::<Panics>(id).is_none());
+
+        assert!(store.remove_typed::<Panics>(id).is_none());
+        assert!(store.contains(&id));
+        assert_eq!(store.remove_typed::<Text>(id).unwrap().0, "hello world");
+        assert!(!store.contains(&id));
+    }
+
+    #[test]
+    fn import_errors_are_categorized() {
+        let (fs, db) = setup();
+        let bare = fs.config().assets().join("README");
+        let unknown = fs.config().assets().join("b.xyz");
+        fs.write(&bare, "a").unwrap();
+        fs.write(&unknown, "b").unwrap();
+
+        let report = import_assets(&[&bare, &unknown], &fs, &db, false);
+        let kind = |path: &Path| {
+            let error = report.errors().iter().find(|error| error.path == path);
+            error.map(|error| error.kind().clone())
+        };
+        assert_eq!(kind(&bare), Some(ImportErrorKind::MissingExtension));
+        assert_eq!(
+            kind(&unknown),
+            Some(ImportErrorKind::NoImporter { ext: "xyz".into() })
+        );
+    }
+
+    struct Include(String);
+
+    impl Asset for Include {}
+
+    impl AssetImporter for Include {
+        type Asset = Include;
+        type Settings = TextSettings;
+        type Saver = Include;
+        type Error = AssetIoError;
+
+        fn import(ctx: &mut LoadContext<Self::Settings>) -> Result<Include, AssetIoError> {
+            let path = String::from_utf8_lossy(ctx.bytes()).trim().to_string();
+            let bytes = ctx.load_dependency_bytes(path)?;
+            Ok(Include(String::from_utf8_lossy(&bytes).to_string()))
+        }
+
+        fn extensions() -> &'static [&'static str] {
+            &["inc"]
+        }
+    }
+
+    impl AssetSaver for Include {
+        type Asset = Include;
+        type Settings = TextSettings;
+        type Error = std::io::Error;
+
+        fn save(asset: &Include, _: &AssetMetadata<TextSettings>) -> std::io::Result<Vec<u8>> {
+            Ok(asset.0.as_bytes().to_vec())
+        }
+
+        fn load(bytes: &[u8]) -> std::io::Result<Include> {
+            Ok(Include(String::from_utf8_lossy(bytes).to_string()))
+        }
+    }
+
+    #[test]
+    fn loaded_dependency_bytes_are_tracked() {
+        let (fs, db) = setup();
+        db.register_importer::<Include>();
+        let assets = fs.config().assets();
+        let (header, text) = (assets.join("header.inc"), assets.join("shared/body.txt"));
+        fs.write(&header, "shared/body.txt").unwrap();
+        fs.write(&text, "included").unwrap();
+        assert!(db.import_paths(&[&header, &text], &fs).is_ok());
+
+        let header_id = *db.library().path_id(&header).unwrap();
+        let text_id = *db.library().path_id(&text).unwrap();
+        let artifact = fs.load_artifact(&header_id).unwrap();
+        assert_eq!(artifact.asset(), b"included");
+        assert!(artifact.meta().dependencies().contains(&text_id));
+        assert!(db.dependents(&text_id).contains(&header_id));
     }
 }
@@ -1,12 +1,17 @@
-use crate::asset::AssetId;
+use crate::asset::{
+    bytes::IntoBytes,
+    io::{AssetFileSystem, AssetIoError},
+    AssetId,
+};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
 };
 
 pub struct AssetLibrary {
     ids: HashMap<PathBuf, AssetId>,
     paths: HashMap<AssetId, PathBuf>,
+    dependents: HashMap<AssetId, HashSet<AssetId>>,
 }
 
 impl AssetLibrary {
@@ -14,12 +19,21 @@ impl AssetLibrary {
         AssetLibrary {
             ids: HashMap::new(),
             paths: HashMap::new(),
+            dependents: HashMap::new(),
         }
     }
 
     pub fn insert(&mut self, id: AssetId, path: PathBuf) -> (Option<AssetId>, Option<PathBuf>) {
         let ret_id = self.ids.insert(path.clone(), id);
-        let ret_path = self.paths.insert(id, path);
+        let ret_path = self.paths.insert(id, path.clone());
+
+        if let Some(old_id) = ret_id.filter(|old_id| *old_id != id) {
+            self.paths.remove(&old_id);
+        }
+
+        if let Some(old_path) = ret_path.as_ref().filter(|old_path| **old_path != path) {
+            self.ids.remove(old_path);
+        }
 
         (ret_id, ret_path)
     }
@@ -31,4 +45,181 @@ impl AssetLibrary {
     pub fn path_id(&self, path: &Path) -> Option<&AssetId> {
         self.ids.get(path)
     }
+
+    /// Removes an asset and every dependency edge it takes part in.
+    pub fn remove(&mut self, id: &AssetId) -> Option<PathBuf> {
+        let path = self.paths.remove(id)?;
+        self.ids.remove(&path);
+        self.dependents.remove(id);
+        self.dependents.retain(|_, dependents| {
+            dependents.remove(id);
+            !dependents.is_empty()
+        });
+        Some(path)
+    }
+
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&AssetId, &PathBuf)> {
+        self.paths.iter()
+    }
+
+    pub fn dependents(&self, id: &AssetId) -> Option<&HashSet<AssetId>> {
+        self.dependents.get(id)
+    }
+
+    pub fn add_dependent(&mut self, id: AssetId, dependent: AssetId) {
+        self.dependents.entry(id).or_default().insert(dependent);
+    }
+
+    pub fn remove_dependent(&mut self, id: &AssetId, dependent: &AssetId) {
+        if let Some(dependents) = self.dependents.get_mut(id) {
+            dependents.remove(dependent);
+            if dependents.is_empty() {
+                self.dependents.remove(id);
+            }
+        }
+    }
+
+    pub fn save(&self, fs: &AssetFileSystem) -> Result<(), AssetIoError> {
+        let path = fs.config().library();
+        if let Some(parent) = path.parent() {
+            fs.create_dir(parent)?;
+        }
+
+        fs.write(path, self.into_bytes())
+    }
+
+    pub fn load(fs: &AssetFileSystem) -> Result<Self, AssetIoError> {
+        let bytes = fs.read(fs.config().library())?;
+        AssetLibrary::from_bytes(&bytes)
+            .ok_or(AssetIoError::InvalidData("Could not read library.".into()))
+    }
+}
+
+impl IntoBytes for AssetLibrary {
+    fn into_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend(self.ids.len().into_bytes());
+        for (path, id) in &self.ids {
+            let path = path.to_string_lossy().to_string();
+            bytes.extend(id.into_bytes());
+            bytes.extend(IntoBytes::into_bytes(&path));
+        }
+
+        bytes.extend(self.dependents.len().into_bytes());
+        for (id, dependents) in &self.dependents {
+            let dependents = dependents.into_bytes();
+            bytes.extend(id.into_bytes());
+            bytes.extend(dependents.len().into_bytes());
+            bytes.extend(dependents);
+        }
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut library = AssetLibrary::new();
+        let mut bytes = bytes;
+        let len = usize::from_bytes(bytes.get(0..8)?)?;
+        bytes = &bytes[8..];
+        for _ in 0..len {
+            let id = AssetId::from_bytes(bytes.get(0..8)?)?;
+            bytes = &bytes[8..];
+            let path = String::from_bytes(bytes)?;
+            bytes = &bytes[8 + path.len()..];
+            library.insert(id, PathBuf::from(path));
+        }
+
+        let len = usize::from_bytes(bytes.get(0..8)?)?;
+        bytes = &bytes[8..];
+        for _ in 0..len {
+            let id = AssetId::from_bytes(bytes.get(0..8)?)?;
+            let set_len = usize::from_bytes(bytes.get(8..16)?)?;
+            bytes = &bytes[16..];
+            let dependents = HashSet::<AssetId>::from_bytes(bytes.get(0..set_len)?)?;
+            bytes = &bytes[set_len..];
+            library.dependents.insert(id, dependents);
+        }
+        Some(library)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset::{config::AssetConfig, io::memory::MemoryFileSystem};
+
+    fn fs() -> AssetFileSystem {
+        AssetFileSystem::new(AssetConfig::new("project"), MemoryFileSystem::new())
+    }
+
+    #[test]
+    fn library_round_trips_through_the_file_system() {
+        let fs = fs();
+        let mut library = AssetLibrary::new();
+        let (a, b) = (AssetId::gen(), AssetId::gen());
+        library.insert(a, PathBuf::from("assets/a.txt"));
+        library.insert(b, PathBuf::from("assets/nested/b.txt"));
+        library.save(&fs).unwrap();
+
+        let loaded = AssetLibrary::load(&fs).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded.id_path(&a), Some(&PathBuf::from("assets/a.txt")));
+        assert_eq!(loaded.path_id(Path::new("assets/nested/b.txt")), Some(&b));
+    }
+
+    #[test]
+    fn reassigning_a_path_drops_the_old_id() {
+        let mut library = AssetLibrary::new();
+        let (old, new) = (AssetId::gen(), AssetId::gen());
+        let path = PathBuf::from("assets/a.txt");
+        library.insert(old, path.clone());
+
+        assert_eq!(library.insert(new, path.clone()), (Some(old), None));
+        assert_eq!(library.id_path(&old), None);
+        assert_eq!(library.id_path(&new), Some(&path));
+        assert_eq!(library.path_id(&path), Some(&new));
+    }
+
+    #[test]
+    fn remove_and_iterate() {
+        let mut library = AssetLibrary::new();
+        let ids = (0..3).map(|_| AssetId::gen()).collect::<Vec<_>>();
+        for (index, id) in ids.iter().enumerate() {
+            library.insert(*id, PathBuf::from(format!("{}.txt", index)));
+        }
+
+        assert_eq!(library.remove(&ids[1]), Some(PathBuf::from("1.txt")));
+        assert_eq!(library.remove(&ids[1]), None);
+        assert_eq!(library.path_id(Path::new("1.txt")), None);
+        assert_eq!(library.len(), 2);
+
+        let mut remaining = library.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+        remaining.sort();
+        let mut expected = vec![ids[0], ids[2]];
+        expected.sort();
+        assert_eq!(remaining, expected);
+    }
+
+    #[test]
+    fn dependents_survive_a_save_load_round_trip() {
+        let fs = fs();
+        let mut library = AssetLibrary::new();
+        let (a, b, c) = (AssetId::gen(), AssetId::gen(), AssetId::gen());
+        library.insert(a, PathBuf::from("a.txt"));
+        library.add_dependent(a, b);
+        library.add_dependent(a, c);
+        library.remove_dependent(&a, &c);
+        library.save(&fs).unwrap();
+
+        let loaded = AssetLibrary::load(&fs).unwrap();
+        assert_eq!(loaded.dependents(&a), Some(&HashSet::from([b])));
+        assert_eq!(loaded.dependents(&b), None);
+    }
 }
@@ -1,28 +1,664 @@
-use importer::AssetImporters;
+use super::{
+    config::AssetConfig,
+    io::{AssetFileSystem, AssetIoError},
+    Asset, AssetId, AssetType, PathExt,
+};
+use crate::background::TaskPool;
+use importer::{
+    AssetImporter, AssetImporters, AssetProcessor, CustomError, ImportError, ImportErrorKind,
+    ImportObserver, ImportReport, LoadedAsset, SavedAsset,
+};
 use library::AssetLibrary;
-use std::sync::{Arc, RwLock, RwLockReadGuard};
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
 
 pub mod importer;
 pub mod library;
 
+#[derive(Default)]
+pub struct RefreshReport {
+    added: usize,
+    changed: usize,
+    removed: usize,
+    import: ImportReport,
+}
+
+impl RefreshReport {
+    pub fn added(&self) -> usize {
+        self.added
+    }
+
+    pub fn changed(&self) -> usize {
+        self.changed
+    }
+
+    pub fn removed(&self) -> usize {
+        self.removed
+    }
+
+    pub fn import(&self) -> &ImportReport {
+        &self.import
+    }
+}
+
+#[derive(Clone)]
 pub struct AssetDatabase {
     library: Arc<RwLock<AssetLibrary>>,
     importers: Arc<RwLock<AssetImporters>>,
+    observer: Arc<RwLock<ImportObserver>>,
+    sources: Arc<RwLock<HashMap<AssetId, PathBuf>>>,
+    pool: Arc<TaskPool>,
 }
 
 impl AssetDatabase {
     pub fn new() -> Self {
+        let size = std::thread::available_parallelism()
+            .map(|size| size.get())
+            .unwrap_or(1);
+
         Self {
             library: Arc::new(RwLock::new(AssetLibrary::new())),
             importers: Arc::new(RwLock::new(AssetImporters::new())),
+            observer: Arc::new(RwLock::new(ImportObserver::new())),
+            sources: Arc::new(RwLock::new(HashMap::new())),
+            pool: Arc::new(TaskPool::new(size)),
         }
     }
 
-    pub fn library(&self) -> RwLockReadGuard<AssetLibrary> {
+    pub fn library(&self) -> RwLockReadGuard<'_, AssetLibrary> {
         self.library.read().unwrap()
     }
 
-    pub fn importers(&self) -> RwLockReadGuard<AssetImporters> {
+    pub fn library_mut(&self) -> RwLockWriteGuard<'_, AssetLibrary> {
+        self.library.write().unwrap()
+    }
+
+    pub fn importers(&self) -> RwLockReadGuard<'_, AssetImporters> {
         self.importers.read().unwrap()
     }
+
+    pub fn importers_mut(&self) -> RwLockWriteGuard<'_, AssetImporters> {
+        self.importers.write().unwrap()
+    }
+
+    pub fn observer(&self) -> RwLockReadGuard<'_, ImportObserver> {
+        self.observer.read().unwrap()
+    }
+
+    /// Sets a callback run after each asset is imported and saved. Setting an
+    /// observer from inside an observer callback deadlocks.
+    pub fn set_import_observer(&self, callback: impl Fn(&SavedAsset) + Send + Sync + 'static) {
+        self.observer
+            .write()
+            .unwrap()
+            .set_on_import(Box::new(callback));
+    }
+
+    /// Sets a callback run for each asset that fails to import.
+    pub fn set_import_error_observer(
+        &self,
+        callback: impl Fn(&ImportError) + Send + Sync + 'static,
+    ) {
+        self.observer
+            .write()
+            .unwrap()
+            .set_on_error(Box::new(callback));
+    }
+
+    /// The pool imports run on. It is shared by every clone of the database.
+    pub fn pool(&self) -> &TaskPool {
+        &self.pool
+    }
+
+    pub fn register_importer<I: AssetImporter>(&self) {
+        self.importers_mut().register::<I>();
+    }
+
+    pub fn register_processor<P: AssetProcessor>(&self) {
+        self.importers_mut().register_processor::<P>();
+    }
+
+    pub fn import_paths<A: AsRef<Path>>(
+        &self,
+        paths: &[A],
+        fs: &Arc<AssetFileSystem>,
+    ) -> ImportReport {
+        importer::full_import(paths, fs, self, false)
+    }
+
+    pub fn force_import_paths<A: AsRef<Path>>(
+        &self,
+        paths: &[A],
+        fs: &Arc<AssetFileSystem>,
+    ) -> ImportReport {
+        importer::full_import(paths, fs, self, true)
+    }
+
+    /// Re-imports an asset and its dependents even if its source is unchanged.
+    /// Returns `None` if the id is not in the library.
+    pub fn reimport(&self, id: &AssetId, fs: &Arc<AssetFileSystem>) -> Option<ImportReport> {
+        let path = self.library().id_path(id).cloned()?;
+        Some(self.force_import_paths(&[path], fs))
+    }
+
+    /// Loads an asset from its artifact, importing it first if the artifact is
+    /// missing, stale, or fails its checksum. Assets that were never imported are
+    /// found by scanning the assets directory for a source with a matching id.
+    #[allow(clippy::result_large_err)]
+    pub fn load<A: Asset>(
+        &self,
+        id: &AssetId,
+        fs: &Arc<AssetFileSystem>,
+    ) -> Result<LoadedAsset, ImportError> {
+        let library_path = self.library().id_path(id).cloned();
+        let path = match library_path.or_else(|| self.find_source(id, fs)) {
+            Some(path) => path,
+            None => {
+                let error = CustomError::from("No source found for asset.");
+                return Err(ImportError::new("", *id, ImportErrorKind::NotFound, error));
+            }
+        };
+
+        let cached = match importer::is_up_to_date(&path, fs, self) {
+            true => fs.load_artifact_verified(id).ok(),
+            false => None,
+        };
+
+        let artifact = match cached {
+            Some(artifact) => artifact,
+            None => {
+                let mut report = self.force_import_paths(&[&path], fs);
+                if let Some(error) = report.take_errors().into_iter().next() {
+                    return Err(error);
+                }
+
+                fs.load_artifact_verified(id)
+                    .map_err(|e| ImportError::new(&path, *id, ImportErrorKind::IoError, e))?
+            }
+        };
+
+        if artifact.meta().ty() != AssetType::from::<A>() {
+            let error = CustomError::from("Asset is not of the requested type.");
+            return Err(ImportError::new(
+                &path,
+                *id,
+                ImportErrorKind::TypeMismatch,
+                error,
+            ));
+        }
+
+        let importers = self.importers();
+        let importer = importers
+            .importer(AssetType::from::<A>())
+            .ok_or(ImportError::new(
+                &path,
+                *id,
+                ImportErrorKind::NoImporter {
+                    ext: path.ext().unwrap_or_default().to_string(),
+                },
+                CustomError::from("No importer found for asset type"),
+            ))?;
+
+        importer
+            .load(artifact)
+            .map_err(|e| ImportError::new(&path, *id, ImportErrorKind::IoError, e))
+    }
+
+    /// Finds the source of an asset that isn't in the library. The ids of every
+    /// source are cached, so the assets directory is only rescanned when the id
+    /// is missing from the last scan or its source has moved.
+    fn find_source(&self, id: &AssetId, fs: &AssetFileSystem) -> Option<PathBuf> {
+        let cached = self.sources.read().unwrap().get(id).cloned();
+        if let Some(path) = cached.filter(|path| fs.exists(path)) {
+            return Some(path);
+        }
+
+        let sources = Self::scan(fs.config().assets(), fs)
+            .ok()?
+            .into_iter()
+            .map(|path| (fs.asset_id(&path), path))
+            .collect::<HashMap<_, _>>();
+        let path = sources.get(id).cloned();
+        *self.sources.write().unwrap() = sources;
+        path
+    }
+
+    /// The assets that list `id` as a dependency.
+    pub fn dependents(&self, id: &AssetId) -> HashSet<AssetId> {
+        self.library().dependents(id).cloned().unwrap_or_default()
+    }
+
+    pub fn import_directory(
+        &self,
+        dir: impl AsRef<Path>,
+        fs: &Arc<AssetFileSystem>,
+    ) -> Result<ImportReport, AssetIoError> {
+        let paths = Self::scan(dir, fs)?;
+        Ok(self.import_paths(&paths, fs))
+    }
+
+    /// Lists the asset sources under `dir`, skipping metadata, hidden files, and
+    /// anything the assets directory's ignore file excludes.
+    fn scan(dir: impl AsRef<Path>, fs: &AssetFileSystem) -> Result<Vec<PathBuf>, AssetIoError> {
+        let ignore = fs.load_ignore();
+        let assets = fs.config().assets();
+        fs.read_directory_filtered(dir, true, |path| {
+            let relative = path.strip_prefix(assets).unwrap_or(path);
+            path.ext() != Some("meta") && !path.is_hidden() && !ignore.is_ignored(relative)
+        })
+    }
+
+    /// Reconciles the library with the assets directory: imports new files,
+    /// re-imports changed ones, and drops entries and artifacts for deleted ones.
+    pub fn refresh(&self, fs: &Arc<AssetFileSystem>) -> Result<RefreshReport, AssetIoError> {
+        let paths = Self::scan(fs.config().assets(), fs)?
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        let mut report = RefreshReport::default();
+        let removed = self
+            .library()
+            .iter()
+            .filter(|(_, path)| !paths.contains(*path))
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+
+        for id in &removed {
+            self.library_mut().remove(id);
+            let _ = fs.remove(fs.artifact_path(id));
+        }
+        report.removed = removed.len();
+
+        let mut imports = Vec::new();
+        for path in paths {
+            let importers = self.importers();
+            let importable = importers
+                .path_ext(&path)
+                .is_some_and(|ext| importers.importer_by_ext(ext).is_some());
+            drop(importers);
+            if !importable {
+                continue;
+            } else if self.library().path_id(&path).is_none() {
+                report.added += 1;
+                imports.push(path);
+            } else if !importer::is_up_to_date(&path, fs, self) {
+                report.changed += 1;
+                imports.push(path);
+            }
+        }
+
+        // Every path left was already checked, so skip the up-to-date check.
+        report.import = importer::full_import(&imports, fs, self, true);
+        Ok(report)
+    }
+
+    /// Moves an asset's source and metadata, keeping its id so dependents still
+    /// resolve it.
+    pub fn rename_asset(
+        &self,
+        from: impl AsRef<Path>,
+        to: impl AsRef<Path>,
+        fs: &Arc<AssetFileSystem>,
+    ) -> Result<AssetId, AssetIoError> {
+        let (from, to) = (from.as_ref(), to.as_ref());
+        let id = *self
+            .library()
+            .path_id(from)
+            .ok_or(AssetIoError::NotFound(from.to_path_buf()))?;
+
+        if let Some(parent) = to.parent() {
+            fs.create_dir(parent)?;
+        }
+
+        fs.rename(from, to)?;
+        if fs.exists(AssetConfig::metadata(from)) {
+            fs.rename(AssetConfig::metadata(from), AssetConfig::metadata(to))?;
+        }
+
+        self.library_mut().insert(id, to.to_path_buf());
+        Ok(id)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{importer::*, AssetDatabase};
+    use crate::asset::AssetType;
+    use crate::asset::{artifact::ArtifactMeta, AssetId, SettingsType};
+    use crate::asset::{
+        config::AssetConfig,
+        io::{memory::MemoryFileSystem, AssetFileSystem},
+        Asset, AssetMetadata, Settings,
+    };
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    pub struct Text(pub String);
+
+    impl Asset for Text {}
+
+    #[derive(Default, serde::Serialize, serde::Deserialize)]
+    pub struct TextSettings {
+        pub upper: bool,
+    }
+
+    impl Settings for TextSettings {}
+
+    pub struct TextImporter;
+
+    impl AssetImporter for TextImporter {
+        type Asset = Text;
+        type Settings = TextSettings;
+        type Saver = TextImporter;
+        type Error = std::io::Error;
+
+        fn import(ctx: &mut LoadContext<Self::Settings>) -> Result<Text, std::io::Error> {
+            let text = String::from_utf8_lossy(ctx.bytes()).to_string();
+            match ctx.metadata().settings().upper {
+                true => Ok(Text(text.to_uppercase())),
+                false => Ok(Text(text)),
+            }
+        }
+
+        fn extensions() -> &'static [&'static str] {
+            &["txt"]
+        }
+    }
+
+    impl AssetSaver for TextImporter {
+        type Asset = Text;
+        type Settings = TextSettings;
+        type Error = std::io::Error;
+
+        fn save(asset: &Text, _: &AssetMetadata<TextSettings>) -> Result<Vec<u8>, std::io::Error> {
+            Ok(asset.0.as_bytes().to_vec())
+        }
+
+        fn load(bytes: &[u8]) -> Result<Text, std::io::Error> {
+            Ok(Text(String::from_utf8_lossy(bytes).to_string()))
+        }
+    }
+
+    /// An in-memory project with [`TextImporter`] registered.
+    pub fn setup() -> (Arc<AssetFileSystem>, AssetDatabase) {
+        let config = AssetConfig::new("project");
+        let fs = Arc::new(AssetFileSystem::new(config, MemoryFileSystem::new()));
+        fs.create_dir(fs.config().assets()).unwrap();
+        let db = AssetDatabase::new();
+        db.register_importer::<TextImporter>();
+        (fs, db)
+    }
+
+    #[test]
+    fn import_directory_imports_every_asset() {
+        let (fs, db) = setup();
+        let assets = fs.config().assets().to_path_buf();
+        fs.write(assets.join("a.txt"), "a").unwrap();
+        fs.write(assets.join("nested/b.txt"), "b").unwrap();
+        fs.write(assets.join("unknown.bin"), "?").unwrap();
+
+        let report = db.import_directory(&assets, &fs).unwrap();
+        let kinds = report.errors().iter().map(|e| e.kind()).collect::<Vec<_>>();
+        let ext = String::from("bin");
+        assert_eq!(kinds, [&ImportErrorKind::NoImporter { ext }]);
+
+        let library = db.library();
+        assert_eq!(library.len(), 2);
+        for (path, text) in [("a.txt", "a"), ("nested/b.txt", "b")] {
+            let id = library.path_id(&assets.join(path)).unwrap();
+            let artifact = fs.load_artifact(id).unwrap();
+            assert_eq!(artifact.asset(), text.as_bytes());
+            assert!(fs.exists(AssetConfig::metadata(assets.join(path))));
+        }
+    }
+
+    #[test]
+    fn refresh_tracks_added_changed_and_removed_files() {
+        let (fs, db) = setup();
+        let assets = fs.config().assets().to_path_buf();
+        fs.write(assets.join("a.txt"), "a").unwrap();
+        fs.write(assets.join("b.txt"), "b").unwrap();
+
+        let report = db.refresh(&fs).unwrap();
+        assert_eq!(
+            (report.added(), report.changed(), report.removed()),
+            (2, 0, 0)
+        );
+        assert!(report.import().is_ok());
+
+        let removed = *db.library().path_id(&assets.join("b.txt")).unwrap();
+        fs.write(assets.join("a.txt"), "changed").unwrap();
+        fs.remove(assets.join("b.txt")).unwrap();
+        fs.write(assets.join("c.txt"), "c").unwrap();
+
+        let report = db.refresh(&fs).unwrap();
+        assert_eq!(
+            (report.added(), report.changed(), report.removed()),
+            (1, 1, 1)
+        );
+        assert_eq!(db.library().len(), 2);
+        assert!(!fs.exists(fs.artifact_path(&removed)));
+    }
+
+    #[test]
+    fn refresh_drops_removed_assets_from_dependents() {
+        let (fs, db) = setup();
+        let assets = fs.config().assets().to_path_buf();
+        fs.write(assets.join("a.txt"), "a").unwrap();
+        fs.write(assets.join("b.txt"), "b").unwrap();
+        db.refresh(&fs).unwrap();
+
+        let a = *db.library().path_id(&assets.join("a.txt")).unwrap();
+        let b = *db.library().path_id(&assets.join("b.txt")).unwrap();
+        db.library_mut().add_dependent(a, b);
+        db.library_mut().add_dependent(b, a);
+        fs.remove(assets.join("b.txt")).unwrap();
+
+        db.refresh(&fs).unwrap();
+        assert!(db.dependents(&a).is_empty());
+        assert!(db.dependents(&b).is_empty());
+    }
+
+    #[test]
+    fn registered_importers_resolve_by_extension() {
+        let db = AssetDatabase::new();
+        assert!(db.importers().importer_by_ext("txt").is_none());
+
+        db.register_importer::<TextImporter>();
+        let importers = db.importers();
+        let importer = importers.importer_by_ext("txt").unwrap();
+        assert_eq!(importer.ty(), AssetType::from::<Text>());
+        assert!(importers.importer_by_ext("png").is_none());
+    }
+
+    #[test]
+    fn settings_type_mismatch_forces_reimport() {
+        let (fs, db) = setup();
+        let path = fs.config().assets().join("a.txt");
+        fs.write(&path, "hello").unwrap();
+        assert!(db.import_paths(&[&path], &fs).is_ok());
+
+        let id = *db.library().path_id(&path).unwrap();
+        let mut artifact = fs.load_artifact(&id).unwrap();
+        let meta = artifact.meta().clone();
+        *artifact.meta_mut() = ArtifactMeta::new(
+            meta.id(),
+            meta.ty(),
+            SettingsType::dynamic(0),
+            meta.checksum(),
+            meta.modified(),
+            meta.dependencies().clone(),
+        );
+        fs.save_artifact(&artifact).unwrap();
+        assert!(!is_up_to_date(&path, &fs, &db));
+
+        let loaded = db.load::<Text>(&id, &fs).unwrap();
+        assert_eq!(loaded.asset::<Text>().0, "hello");
+        let settings = fs.load_artifact_meta(&id).unwrap().settings();
+        assert_eq!(settings, SettingsType::from::<TextSettings>());
+        assert!(is_up_to_date(&path, &fs, &db));
+    }
+
+    #[test]
+    fn renamed_dependencies_still_load() {
+        let (fs, db) = setup();
+        let assets = fs.config().assets().to_path_buf();
+        fs.write(assets.join("a.txt"), "a").unwrap();
+        fs.write(assets.join("b.txt"), "b").unwrap();
+        db.refresh(&fs).unwrap();
+
+        let a = *db.library().path_id(&assets.join("a.txt")).unwrap();
+        let b = *db.library().path_id(&assets.join("b.txt")).unwrap();
+        db.library_mut().add_dependent(b, a);
+
+        let moved = assets.join("shared/b.txt");
+        assert_eq!(db.rename_asset(assets.join("b.txt"), &moved, &fs), Ok(b));
+        assert!(!fs.exists(assets.join("b.txt")));
+        assert_eq!(db.library().id_path(&b), Some(&moved));
+        assert!(db.dependents(&b).contains(&a));
+
+        let loaded = db.load::<Text>(&b, &fs).unwrap();
+        assert_eq!(loaded.asset::<Text>().0, "b");
+    }
+
+    #[test]
+    fn ignored_paths_are_not_imported() {
+        let (fs, db) = setup();
+        let assets = fs.config().assets().to_path_buf();
+        fs.write(fs.config().ignore(), "# build output\nbuild/\n*.tmp.txt\n")
+            .unwrap();
+        for name in ["a.txt", "build/b.txt", "build/nested/c.txt", "d.tmp.txt"] {
+            fs.write(assets.join(name), name).unwrap();
+        }
+
+        assert!(db.import_directory(&assets, &fs).unwrap().is_ok());
+        let library = db.library();
+        assert!(library.path_id(&assets.join("a.txt")).is_some());
+        for name in [
+            "build/b.txt",
+            "build/nested/c.txt",
+            "d.tmp.txt",
+            ".gobignore",
+        ] {
+            assert!(library.path_id(&assets.join(name)).is_none(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn reimport_rewrites_unchanged_assets() {
+        let (fs, db) = setup();
+        let path = fs.config().assets().join("a.txt");
+        fs.write(&path, "hello").unwrap();
+        assert!(db.import_paths(&[&path], &fs).is_ok());
+
+        let id = *db.library().path_id(&path).unwrap();
+        let artifact = fs.config().artifact(&id);
+        let written = fs.file_metadata(&artifact).unwrap().modified();
+        assert!(is_up_to_date(&path, &fs, &db));
+
+        assert!(db.reimport(&id, &fs).unwrap().is_ok());
+        assert_ne!(fs.file_metadata(&artifact).unwrap().modified(), written);
+        assert!(db.reimport(&AssetId::gen(), &fs).is_none());
+    }
+
+    #[test]
+    fn dependents_lists_assets_that_depend_on_an_id() {
+        let (fs, db) = setup();
+        let assets = fs.config().assets().to_path_buf();
+        for name in ["a.txt", "b.txt", "c.txt"] {
+            fs.write(assets.join(name), name).unwrap();
+        }
+        db.refresh(&fs).unwrap();
+
+        let id = |name: &str| *db.library().path_id(&assets.join(name)).unwrap();
+        let (a, b, c) = (id("a.txt"), id("b.txt"), id("c.txt"));
+        db.library_mut().add_dependent(b, a);
+
+        assert_eq!(db.dependents(&b), [a].into_iter().collect());
+        assert!(db.dependents(&a).is_empty());
+        assert!(db.dependents(&c).is_empty());
+        assert!(db.dependents(&AssetId::gen()).is_empty());
+    }
+
+    #[test]
+    fn load_imports_assets_that_were_never_imported() {
+        let (fs, db) = setup();
+        let path = fs.config().assets().join("notes/a.txt");
+        fs.write(&path, "fresh").unwrap();
+        let id = fs.asset_id(&path);
+        assert!(db.library().id_path(&id).is_none());
+
+        let loaded = db.load::<Text>(&id, &fs).unwrap();
+        assert_eq!(loaded.asset::<Text>().0, "fresh");
+        assert_eq!(db.library().id_path(&id), Some(&path));
+        assert!(fs.load_artifact_verified(&id).is_ok());
+
+        let missing = db.load::<Text>(&AssetId::gen(), &fs).err().unwrap();
+        assert_eq!(missing.kind(), &ImportErrorKind::NotFound);
+    }
+
+    #[test]
+    fn load_finds_unimported_sources_from_one_scan() {
+        let (fs, db) = setup();
+        let assets = fs.config().assets().to_path_buf();
+        let (a, b, c) = (
+            assets.join("a.txt"),
+            assets.join("b.txt"),
+            assets.join("c.txt"),
+        );
+        fs.write(&a, "a").unwrap();
+        fs.write(&b, "b").unwrap();
+        assert!(db.load::<Text>(&fs.asset_id(&a), &fs).is_ok());
+        assert_eq!(db.sources.read().unwrap().len(), 2);
+
+        fs.write(&c, "c").unwrap();
+        assert!(db.load::<Text>(&fs.asset_id(&b), &fs).is_ok());
+        assert_eq!(db.sources.read().unwrap().len(), 2);
+
+        assert!(db.load::<Text>(&fs.asset_id(&c), &fs).is_ok());
+        assert_eq!(db.sources.read().unwrap().len(), 3);
+    }
+
+    struct Other;
+
+    impl Asset for Other {}
+
+    #[test]
+    fn load_rejects_assets_of_another_type() {
+        let (fs, db) = setup();
+        let path = fs.config().assets().join("a.txt");
+        fs.write(&path, "a").unwrap();
+
+        let error = db.load::<Other>(&fs.asset_id(&path), &fs).err().unwrap();
+        assert_eq!(error.kind(), &ImportErrorKind::TypeMismatch);
+        assert_eq!(error.path, path);
+    }
+
+    #[test]
+    fn observers_see_every_import_in_a_batch() {
+        let (fs, db) = setup();
+        let imported = Arc::new(AtomicUsize::new(0));
+        let failed = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&imported);
+        db.set_import_observer(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        let counter = Arc::clone(&failed);
+        db.set_import_error_observer(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let assets = fs.config().assets().to_path_buf();
+        for name in ["a.txt", "b.txt", "c.txt", "d.xyz"] {
+            fs.write(assets.join(name), name).unwrap();
+        }
+        let report = db.import_directory(&assets, &fs).unwrap();
+
+        assert_eq!(report.errors().len(), 1);
+        assert_eq!(imported.load(Ordering::SeqCst), 3);
+        assert_eq!(failed.load(Ordering::SeqCst), 1);
+    }
 }
@@ -0,0 +1,121 @@
+use std::path::{Component, Path};
+
+/// A path pattern supporting `*` (any run of characters within a component), `**`
+/// (any run across components), and `?` (a single character).
+///
+/// A pattern without a `/` is matched against the file name. Otherwise it is matched
+/// against the trailing components of the path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Glob {
+    pattern: String,
+}
+
+impl Glob {
+    pub fn new(pattern: impl Into<String>) -> Self {
+        Self {
+            pattern: pattern.into(),
+        }
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn matches(&self, path: impl AsRef<Path>) -> bool {
+        let parts = path
+            .as_ref()
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(part) => part.to_str(),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        if !self.pattern.contains('/') {
+            return parts
+                .last()
+                .is_some_and(|name| wildcard(self.pattern.as_bytes(), name.as_bytes()));
+        }
+
+        let pattern = self.pattern.trim_start_matches('/');
+        (0..parts.len()).any(|start| {
+            let path = parts[start..].join("/");
+            wildcard(pattern.as_bytes(), path.as_bytes())
+        })
+    }
+}
+
+impl From<&str> for Glob {
+    fn from(pattern: &str) -> Self {
+        Glob::new(pattern)
+    }
+}
+
+/// Patterns read from an ignore file, one per line. Blank lines and lines starting
+/// with `#` are skipped, and a pattern ending in `/` matches a directory and
+/// everything beneath it.
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreList {
+    files: Vec<Glob>,
+    dirs: Vec<Glob>,
+}
+
+impl IgnoreList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let mut list = Self::new();
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            list.add(line);
+        }
+
+        list
+    }
+
+    pub fn add(&mut self, pattern: &str) -> &mut Self {
+        match pattern.strip_suffix('/') {
+            Some(dir) => self.dirs.push(Glob::new(dir)),
+            None => self.files.push(Glob::new(pattern)),
+        }
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty() && self.dirs.is_empty()
+    }
+
+    /// Checks a path relative to the directory the ignore file lives in.
+    pub fn is_ignored(&self, path: impl AsRef<Path>) -> bool {
+        let path = path.as_ref();
+        if self.files.iter().any(|glob| glob.matches(path)) {
+            return true;
+        }
+
+        path.ancestors()
+            .skip(1)
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .any(|dir| self.dirs.iter().any(|glob| glob.matches(dir)))
+    }
+}
+
+fn wildcard(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        [b'*', b'*', rest @ ..] => {
+            let rest = rest.strip_prefix(b"/").unwrap_or(rest);
+            (0..=text.len()).any(|i| wildcard(rest, &text[i..]))
+        }
+        [b'*', rest @ ..] => {
+            let end = text.iter().position(|c| *c == b'/').unwrap_or(text.len());
+            (0..=end).any(|i| wildcard(rest, &text[i..]))
+        }
+        [b'?', rest @ ..] => matches!(text, [c, ..] if *c != b'/') && wildcard(rest, &text[1..]),
+        [c, rest @ ..] => text.first() == Some(c) && wildcard(rest, &text[1..]),
+    }
+}
@@ -0,0 +1,165 @@
+use super::{AssetIoError, FileMetadata, FileReader, FileSystem, FileWriter};
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+pub struct HttpFileSystem {
+    base_url: String,
+}
+
+impl HttpFileSystem {
+    pub fn new(base_url: impl AsRef<str>) -> Self {
+        Self {
+            base_url: base_url.as_ref().trim_end_matches('/').to_string(),
+        }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub fn url(&self, path: &Path) -> String {
+        let path = path
+            .components()
+            .filter_map(|c| match c {
+                std::path::Component::Normal(part) => part.to_str(),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+
+        format!("{}/{}", self.base_url, path)
+    }
+
+    fn get(&self, path: &Path) -> Result<ureq::Response, AssetIoError> {
+        self.call("GET", path)
+    }
+
+    fn call(&self, method: &str, path: &Path) -> Result<ureq::Response, AssetIoError> {
+        match ureq::request(method, &self.url(path)).call() {
+            Ok(response) => Ok(response),
+            Err(ureq::Error::Status(404, _)) => Err(AssetIoError::NotFound(path.to_path_buf())),
+            Err(ureq::Error::Status(status, _)) => Err(AssetIoError::Http(status)),
+            Err(ureq::Error::Transport(error)) => {
+                Err(AssetIoError::from_io(std::io::Error::other(error), path))
+            }
+        }
+    }
+}
+
+impl FileSystem for HttpFileSystem {
+    fn read(&self, path: &Path) -> Result<Vec<u8>, AssetIoError> {
+        let mut bytes = Vec::new();
+        self.get(path)?
+            .into_reader()
+            .read_to_end(&mut bytes)
+            .map_err(|e| AssetIoError::from_io(e, path))?;
+        Ok(bytes)
+    }
+
+    fn read_to_string(&self, path: &Path) -> Result<String, AssetIoError> {
+        self.get(path)?
+            .into_string()
+            .map_err(|e| AssetIoError::from_io(e, path))
+    }
+
+    fn read_exact(&self, path: &Path, buffer: &mut [u8]) -> Result<(), AssetIoError> {
+        self.get(path)?
+            .into_reader()
+            .read_exact(buffer)
+            .map_err(|e| AssetIoError::from_io(e, path))
+    }
+
+    fn reader(&self, path: &Path) -> Result<FileReader, AssetIoError> {
+        Ok(FileReader::new(self.get(path)?.into_reader()).with_path(path))
+    }
+
+    fn write(&self, _: &Path, _: &[u8]) -> Result<(), AssetIoError> {
+        Err(AssetIoError::Unsupported)
+    }
+
+    fn writer(&self, _: &Path) -> Result<FileWriter, AssetIoError> {
+        Err(AssetIoError::Unsupported)
+    }
+
+    fn remove(&self, _: &Path) -> Result<Vec<PathBuf>, AssetIoError> {
+        Err(AssetIoError::Unsupported)
+    }
+
+    fn rename(&self, _: &Path, _: &Path) -> Result<(), AssetIoError> {
+        Err(AssetIoError::Unsupported)
+    }
+
+    fn read_directory(&self, _: &Path, _: bool) -> Result<Vec<PathBuf>, AssetIoError> {
+        Err(AssetIoError::Unsupported)
+    }
+
+    fn create_dir(&self, _: &Path) -> Result<(), AssetIoError> {
+        Err(AssetIoError::Unsupported)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.call("HEAD", path).is_ok()
+    }
+
+    fn file_metadata(&self, path: &Path) -> Result<FileMetadata, AssetIoError> {
+        let response = self.call("HEAD", path)?;
+        let len = response
+            .header("Content-Length")
+            .and_then(|len| len.parse().ok())
+            .unwrap_or_default();
+
+        Ok(FileMetadata::new(len, SystemTime::UNIX_EPOCH, false))
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+    };
+
+    /// Serves `/ok` with a body, `/missing` as a 404 and everything else as a 500.
+    fn serve() -> HttpFileSystem {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut line = String::new();
+                BufReader::new(&stream).read_line(&mut line).unwrap();
+                let response = match line.split_whitespace().nth(1) {
+                    Some("/ok") => "HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello",
+                    Some("/missing") => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n",
+                    _ => "HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\n\r\n",
+                };
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        HttpFileSystem::new(url)
+    }
+
+    #[test]
+    fn responses_map_to_io_errors() {
+        let fs = serve();
+        assert_eq!(fs.read(Path::new("ok")).unwrap(), b"hello");
+        assert_eq!(
+            fs.read(Path::new("missing")),
+            Err(AssetIoError::NotFound(PathBuf::from("missing")))
+        );
+        assert_eq!(fs.read(Path::new("broken")), Err(AssetIoError::Http(500)));
+        assert_eq!(
+            fs.write(Path::new("ok"), b""),
+            Err(AssetIoError::Unsupported)
+        );
+    }
+}
@@ -0,0 +1,211 @@
+use super::{AssetIoError, FileMetadata, FileReader, FileSystem, FileWriter};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::{Cursor, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::SystemTime,
+};
+
+#[derive(Default)]
+struct Entries {
+    files: BTreeMap<PathBuf, (Vec<u8>, SystemTime)>,
+    dirs: BTreeSet<PathBuf>,
+}
+
+impl Entries {
+    fn insert(&mut self, path: &Path, data: Vec<u8>) {
+        self.dirs
+            .extend(path.ancestors().skip(1).map(Path::to_path_buf));
+        self.files
+            .insert(path.to_path_buf(), (data, SystemTime::now()));
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.dirs.contains(path)
+    }
+}
+
+/// Keeps every file in memory. Clones share the same files, and writing a file
+/// implicitly creates its parent directories.
+#[derive(Clone, Default)]
+pub struct MemoryFileSystem {
+    entries: Arc<RwLock<Entries>>,
+}
+
+impl MemoryFileSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl FileSystem for MemoryFileSystem {
+    fn read(&self, path: &Path) -> Result<Vec<u8>, AssetIoError> {
+        let entries = self.entries.read().unwrap();
+        match entries.files.get(path) {
+            Some((data, _)) => Ok(data.clone()),
+            None => Err(AssetIoError::NotFound(path.to_path_buf())),
+        }
+    }
+
+    fn read_to_string(&self, path: &Path) -> Result<String, AssetIoError> {
+        String::from_utf8(self.read(path)?).map_err(|e| AssetIoError::InvalidData(e.to_string()))
+    }
+
+    fn read_exact(&self, path: &Path, buffer: &mut [u8]) -> Result<(), AssetIoError> {
+        let data = self.read(path)?;
+        let data = data
+            .get(..buffer.len())
+            .ok_or_else(|| AssetIoError::from_io(std::io::ErrorKind::UnexpectedEof.into(), path))?;
+        buffer.copy_from_slice(data);
+        Ok(())
+    }
+
+    fn reader(&self, path: &Path) -> Result<FileReader, AssetIoError> {
+        Ok(FileReader::new(Cursor::new(self.read(path)?)).with_path(path))
+    }
+
+    fn write(&self, path: &Path, data: &[u8]) -> Result<(), AssetIoError> {
+        let mut entries = self.entries.write().unwrap();
+        if entries.is_dir(path) {
+            return Err(AssetIoError::InvalidData(format!(
+                "{:?} is a directory",
+                path
+            )));
+        }
+
+        entries.insert(path, data.to_vec());
+        Ok(())
+    }
+
+    fn writer(&self, path: &Path) -> Result<FileWriter, AssetIoError> {
+        self.write(path, &[])?;
+        Ok(FileWriter::new(MemoryWriter {
+            path: path.to_path_buf(),
+            buffer: Vec::new(),
+            entries: Arc::clone(&self.entries),
+        })
+        .with_path(path))
+    }
+
+    fn remove(&self, path: &Path) -> Result<Vec<PathBuf>, AssetIoError> {
+        let mut entries = self.entries.write().unwrap();
+        if entries.files.remove(path).is_some() {
+            return Ok(vec![]);
+        } else if !entries.dirs.remove(path) {
+            return Err(AssetIoError::NotFound(path.to_path_buf()));
+        }
+
+        entries.dirs.retain(|dir| !dir.starts_with(path));
+        let removed = entries
+            .files
+            .keys()
+            .filter(|file| file.starts_with(path))
+            .cloned()
+            .collect::<Vec<_>>();
+        for file in &removed {
+            entries.files.remove(file);
+        }
+
+        Ok(removed)
+    }
+
+    fn rename(&self, old: &Path, new: &Path) -> Result<(), AssetIoError> {
+        let mut entries = self.entries.write().unwrap();
+        if let Some((data, _)) = entries.files.remove(old) {
+            entries.insert(new, data);
+            return Ok(());
+        } else if !entries.is_dir(old) {
+            return Err(AssetIoError::NotFound(old.to_path_buf()));
+        }
+
+        let moved = entries
+            .files
+            .keys()
+            .filter(|file| file.starts_with(old))
+            .cloned()
+            .collect::<Vec<_>>();
+        for file in moved {
+            let (data, _) = entries.files.remove(&file).unwrap();
+            entries.insert(&new.join(file.strip_prefix(old).unwrap()), data);
+        }
+
+        let dirs = entries
+            .dirs
+            .iter()
+            .filter(|dir| dir.starts_with(old))
+            .cloned()
+            .collect::<Vec<_>>();
+        for dir in dirs {
+            entries.dirs.remove(&dir);
+            entries
+                .dirs
+                .insert(new.join(dir.strip_prefix(old).unwrap()));
+        }
+
+        Ok(())
+    }
+
+    fn read_directory(&self, path: &Path, recursive: bool) -> Result<Vec<PathBuf>, AssetIoError> {
+        let entries = self.entries.read().unwrap();
+        if !entries.is_dir(path) {
+            return Err(AssetIoError::NotFound(path.to_path_buf()));
+        }
+
+        let paths = entries.files.keys().filter(|file| match recursive {
+            true => file.starts_with(path),
+            false => file.parent() == Some(path),
+        });
+        Ok(paths.cloned().collect())
+    }
+
+    fn create_dir(&self, path: &Path) -> Result<(), AssetIoError> {
+        let mut entries = self.entries.write().unwrap();
+        if entries.files.contains_key(path) {
+            return Err(AssetIoError::InvalidData(format!("{:?} is a file", path)));
+        }
+
+        entries.dirs.extend(path.ancestors().map(Path::to_path_buf));
+        Ok(())
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        let entries = self.entries.read().unwrap();
+        entries.files.contains_key(path) || entries.is_dir(path)
+    }
+
+    fn file_metadata(&self, path: &Path) -> Result<FileMetadata, AssetIoError> {
+        let entries = self.entries.read().unwrap();
+        match entries.files.get(path) {
+            Some((data, modified)) => Ok(FileMetadata::new(data.len() as u64, *modified, false)),
+            None if entries.is_dir(path) => Ok(FileMetadata::new(0, SystemTime::UNIX_EPOCH, true)),
+            None => Err(AssetIoError::NotFound(path.to_path_buf())),
+        }
+    }
+}
+
+/// Buffers writes and stores them in the file system on flush and on drop.
+struct MemoryWriter {
+    path: PathBuf,
+    buffer: Vec<u8>,
+    entries: Arc<RwLock<Entries>>,
+}
+
+impl Write for MemoryWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        let mut entries = self.entries.write().unwrap();
+        entries.insert(&self.path, self.buffer.clone());
+        Ok(())
+    }
+}
+
+impl Drop for MemoryWriter {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
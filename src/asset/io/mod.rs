@@ -1,23 +1,44 @@
 use super::{
-    artifact::{Artifact, ArtifactMeta},
+    artifact::{
+        Artifact, ArtifactCompression, ArtifactMeta, ArtifactReader, Checksum, ARTIFACT_HEADER_LEN,
+        ARTIFACT_VERSION,
+    },
     config::AssetConfig,
     AssetId, AssetMetadata, PathExt, Settings,
 };
 use crate::asset::bytes::IntoBytes;
+use glob::{Glob, IgnoreList};
 use std::{
     error::Error,
-    hash::Hash,
-    io::Read,
+    io::{Read, Write},
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
     time::SystemTime,
 };
 
+pub mod glob;
+#[cfg(feature = "http")]
+pub mod http;
+pub mod memory;
+pub mod overlay;
+pub mod readonly;
+pub mod sandbox;
+#[cfg(feature = "notify")]
+pub mod watch;
+
 #[derive(Debug, Clone)]
 pub enum AssetIoError {
     NotFound(PathBuf),
     Io(Arc<std::io::Error>),
     Http(u16),
+    InvalidHeader(PathBuf),
+    ChecksumMismatch(PathBuf),
+    PermissionDenied(PathBuf),
+    Unsupported,
+    InvalidData(String),
 }
 
 impl PartialEq for AssetIoError {
@@ -26,6 +47,11 @@ impl PartialEq for AssetIoError {
             (AssetIoError::NotFound(a), AssetIoError::NotFound(b)) => a == b,
             (AssetIoError::Io(a), AssetIoError::Io(b)) => a.kind() == b.kind(),
             (AssetIoError::Http(a), AssetIoError::Http(b)) => a == b,
+            (AssetIoError::InvalidHeader(a), AssetIoError::InvalidHeader(b)) => a == b,
+            (AssetIoError::ChecksumMismatch(a), AssetIoError::ChecksumMismatch(b)) => a == b,
+            (AssetIoError::PermissionDenied(a), AssetIoError::PermissionDenied(b)) => a == b,
+            (AssetIoError::Unsupported, AssetIoError::Unsupported) => true,
+            (AssetIoError::InvalidData(a), AssetIoError::InvalidData(b)) => a == b,
             _ => false,
         }
     }
@@ -33,15 +59,18 @@ impl PartialEq for AssetIoError {
 
 impl Eq for AssetIoError {}
 
-impl From<std::io::Error> for AssetIoError {
-    fn from(error: std::io::Error) -> Self {
-        AssetIoError::Io(Arc::new(error))
-    }
-}
-
-impl From<std::io::ErrorKind> for AssetIoError {
-    fn from(kind: std::io::ErrorKind) -> Self {
-        AssetIoError::Io(Arc::new(std::io::Error::from(kind)))
+impl AssetIoError {
+    /// Maps an io error raised while accessing `path`, keeping the path in the
+    /// variants that carry one.
+    pub fn from_io(error: std::io::Error, path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref().to_path_buf();
+        match error.kind() {
+            std::io::ErrorKind::NotFound => AssetIoError::NotFound(path),
+            std::io::ErrorKind::PermissionDenied => AssetIoError::PermissionDenied(path),
+            std::io::ErrorKind::Unsupported => AssetIoError::Unsupported,
+            std::io::ErrorKind::InvalidData => AssetIoError::InvalidData(error.to_string()),
+            _ => AssetIoError::Io(Arc::new(error)),
+        }
     }
 }
 
@@ -51,6 +80,17 @@ impl std::fmt::Display for AssetIoError {
             AssetIoError::NotFound(path) => write!(f, "Asset not found: {:?}", path),
             AssetIoError::Io(error) => write!(f, "IO error: {}", error),
             AssetIoError::Http(status) => write!(f, "HTTP error: {}", status),
+            AssetIoError::InvalidHeader(path) => write!(
+                f,
+                "Not a version {} artifact (bad magic or version): {:?}",
+                ARTIFACT_VERSION, path
+            ),
+            AssetIoError::ChecksumMismatch(path) => {
+                write!(f, "Artifact checksum mismatch: {:?}", path)
+            }
+            AssetIoError::PermissionDenied(path) => write!(f, "Permission denied: {:?}", path),
+            AssetIoError::Unsupported => write!(f, "Unsupported operation"),
+            AssetIoError::InvalidData(message) => write!(f, "Invalid data: {}", message),
         }
     }
 }
@@ -59,71 +99,217 @@ impl Error for AssetIoError {}
 
 pub struct FileReader {
     reader: Box<dyn Read>,
+    path: Option<PathBuf>,
 }
 
 impl FileReader {
     pub fn new<R: Read + 'static>(reader: R) -> Self {
         Self {
             reader: Box::new(reader),
+            path: None,
         }
     }
 
+    /// Sets the path reported in errors.
+    pub fn with_path(mut self, path: impl AsRef<Path>) -> Self {
+        self.path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
     pub fn read_exact(&mut self, buffer: &mut [u8]) -> Result<(), AssetIoError> {
-        self.reader
-            .read_exact(buffer)
-            .map_err(|e| AssetIoError::Io(Arc::new(e)))
+        let result = self.reader.read_exact(buffer);
+        result.map_err(|e| io_error(e, &self.path))
     }
 
     pub fn read(&mut self, buffer: &mut [u8]) -> Result<usize, AssetIoError> {
-        self.reader
-            .read(buffer)
-            .map_err(|e| AssetIoError::Io(Arc::new(e)))
+        let result = self.reader.read(buffer);
+        result.map_err(|e| io_error(e, &self.path))
     }
 
     pub fn read_to_end(&mut self) -> Result<Vec<u8>, AssetIoError> {
         let mut buffer = Vec::new();
-        self.reader
-            .read_to_end(&mut buffer)
-            .map_err(|e| AssetIoError::Io(Arc::new(e)))?;
+        let result = self.reader.read_to_end(&mut buffer);
+        result.map_err(|e| io_error(e, &self.path))?;
         Ok(buffer)
     }
 }
 
+impl Read for FileReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.reader.read(buf)
+    }
+}
+
+pub struct FileWriter {
+    writer: Box<dyn Write>,
+    path: Option<PathBuf>,
+}
+
+impl FileWriter {
+    pub fn new<W: Write + 'static>(writer: W) -> Self {
+        Self {
+            writer: Box::new(writer),
+            path: None,
+        }
+    }
+
+    /// Sets the path reported in errors.
+    pub fn with_path(mut self, path: impl AsRef<Path>) -> Self {
+        self.path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn write_all(&mut self, buffer: &[u8]) -> Result<(), AssetIoError> {
+        let result = self.writer.write_all(buffer);
+        result.map_err(|e| io_error(e, &self.path))
+    }
+
+    pub fn write(&mut self, buffer: &[u8]) -> Result<usize, AssetIoError> {
+        let result = self.writer.write(buffer);
+        result.map_err(|e| io_error(e, &self.path))
+    }
+
+    pub fn flush(&mut self) -> Result<(), AssetIoError> {
+        let result = self.writer.flush();
+        result.map_err(|e| io_error(e, &self.path))
+    }
+}
+
+fn io_error(error: std::io::Error, path: &Option<PathBuf>) -> AssetIoError {
+    match path {
+        Some(path) => AssetIoError::from_io(error, path),
+        None => AssetIoError::Io(Arc::new(error)),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileMetadata {
+    len: u64,
+    modified: SystemTime,
+    is_dir: bool,
+}
+
+impl FileMetadata {
+    pub fn new(len: u64, modified: SystemTime, is_dir: bool) -> Self {
+        Self {
+            len,
+            modified,
+            is_dir,
+        }
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn modified(&self) -> SystemTime {
+        self.modified
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.is_dir
+    }
+}
+
 pub trait FileSystem: Send + Sync + 'static {
     fn read(&self, path: &Path) -> Result<Vec<u8>, AssetIoError>;
     fn read_to_string(&self, path: &Path) -> Result<String, AssetIoError>;
     fn read_exact(&self, path: &Path, buffer: &mut [u8]) -> Result<(), AssetIoError>;
     fn reader(&self, path: &Path) -> Result<FileReader, AssetIoError>;
     fn write(&self, path: &Path, data: &[u8]) -> Result<(), AssetIoError>;
+    fn writer(&self, path: &Path) -> Result<FileWriter, AssetIoError>;
     fn remove(&self, path: &Path) -> Result<Vec<PathBuf>, AssetIoError>;
     fn rename(&self, old: &Path, new: &Path) -> Result<(), AssetIoError>;
     fn read_directory(&self, path: &Path, recursive: bool) -> Result<Vec<PathBuf>, AssetIoError>;
     fn create_dir(&self, path: &Path) -> Result<(), AssetIoError>;
+    fn exists(&self, path: &Path) -> bool;
+    fn file_metadata(&self, path: &Path) -> Result<FileMetadata, AssetIoError>;
+
+    /// Whether every mutating call is expected to fail.
+    fn is_read_only(&self) -> bool {
+        false
+    }
 }
 
 pub struct LocalFileSystem;
 
+impl LocalFileSystem {
+    const TEMP_PREFIX: &'static str = ".gob-tmp-";
+
+    /// A hidden sibling of `path` that no other write, in this process or
+    /// another, will pick.
+    fn temp_path(path: &Path) -> PathBuf {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let next = NEXT.fetch_add(1, Ordering::Relaxed);
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        path.with_file_name(format!(
+            "{}{}-{}-{}",
+            Self::TEMP_PREFIX,
+            std::process::id(),
+            next,
+            name
+        ))
+    }
+
+    /// Whether `path` is a temp file left behind by an interrupted write.
+    pub fn is_temp_path(path: &Path) -> bool {
+        path.file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with(Self::TEMP_PREFIX))
+    }
+}
+
 impl FileSystem for LocalFileSystem {
     fn read(&self, path: &Path) -> Result<Vec<u8>, AssetIoError> {
-        std::fs::read(path).map_err(|e| e.into())
+        std::fs::read(path).map_err(|e| AssetIoError::from_io(e, path))
     }
 
     fn read_exact(&self, path: &Path, buffer: &mut [u8]) -> Result<(), AssetIoError> {
-        let mut file = std::fs::File::open(path)?;
-        file.read_exact(buffer).map_err(|e| e.into())
+        let mut file = std::fs::File::open(path).map_err(|e| AssetIoError::from_io(e, path))?;
+        file.read_exact(buffer)
+            .map_err(|e| AssetIoError::from_io(e, path))
     }
 
     fn read_to_string(&self, path: &Path) -> Result<String, AssetIoError> {
-        std::fs::read_to_string(path).map_err(|e| e.into())
+        std::fs::read_to_string(path).map_err(|e| AssetIoError::from_io(e, path))
     }
 
     fn reader(&self, path: &Path) -> Result<FileReader, AssetIoError> {
-        let file = std::fs::File::open(path)?;
-        Ok(FileReader::new(file))
+        let file = std::fs::File::open(path).map_err(|e| AssetIoError::from_io(e, path))?;
+        Ok(FileReader::new(file).with_path(path))
     }
 
     fn write(&self, path: &Path, data: &[u8]) -> Result<(), AssetIoError> {
-        std::fs::write(path, data).map_err(|e| e.into())
+        let temp = Self::temp_path(path);
+        if let Err(e) = std::fs::write(&temp, data) {
+            let _ = std::fs::remove_file(&temp);
+            return Err(AssetIoError::from_io(e, path));
+        }
+
+        std::fs::rename(&temp, path).map_err(|e| {
+            let _ = std::fs::remove_file(&temp);
+            AssetIoError::from_io(e, path)
+        })
+    }
+
+    /// Streams into a temp sibling that replaces `path` when the writer is dropped,
+    /// so an interrupted write leaves the original intact.
+    fn writer(&self, path: &Path) -> Result<FileWriter, AssetIoError> {
+        let temp = Self::temp_path(path);
+        let file = std::fs::File::create(&temp).map_err(|e| AssetIoError::from_io(e, path))?;
+        let writer = LocalWriter {
+            file: Some(file),
+            temp,
+            path: path.to_path_buf(),
+        };
+        Ok(FileWriter::new(writer).with_path(path))
     }
 
     fn remove(&self, path: &Path) -> Result<Vec<PathBuf>, AssetIoError> {
@@ -132,34 +318,38 @@ impl FileSystem for LocalFileSystem {
             if entries.is_empty() {
                 std::fs::remove_dir(path)
                     .map(|_| vec![])
-                    .map_err(|e| e.into())
+                    .map_err(|e| AssetIoError::from_io(e, path))
             } else {
                 std::fs::remove_dir_all(path)
                     .map(|_| entries)
-                    .map_err(|e| e.into())
+                    .map_err(|e| AssetIoError::from_io(e, path))
             }
         } else if path.is_file() {
             std::fs::remove_file(path)
                 .map(|_| vec![])
-                .map_err(|e| e.into())
+                .map_err(|e| AssetIoError::from_io(e, path))
         } else {
-            Err(std::io::ErrorKind::NotFound.into())
+            Err(AssetIoError::NotFound(path.to_path_buf()))
         }
     }
 
     fn rename(&self, from: &Path, to: &Path) -> Result<(), AssetIoError> {
-        std::fs::rename(from, to).map_err(|e| e.into())
+        std::fs::rename(from, to).map_err(|e| AssetIoError::from_io(e, from))
     }
 
     fn read_directory(&self, path: &Path, recursive: bool) -> Result<Vec<PathBuf>, AssetIoError> {
         let mut paths = vec![];
-        let dir = std::fs::read_dir(path)?;
+        let dir = std::fs::read_dir(path).map_err(|e| AssetIoError::from_io(e, path))?;
 
         for entry in dir {
-            let entry = entry?;
+            let entry = entry.map_err(|e| AssetIoError::from_io(e, path))?;
             let path = entry.path();
             if path.is_dir() {
-                paths.extend(self.read_directory(&path, recursive)?)
+                if recursive {
+                    paths.extend(self.read_directory(&path, recursive)?)
+                }
+            } else if !Self::is_temp_path(&path) {
+                paths.push(path);
             }
         }
 
@@ -167,7 +357,55 @@ impl FileSystem for LocalFileSystem {
     }
 
     fn create_dir(&self, path: &Path) -> Result<(), AssetIoError> {
-        std::fs::create_dir_all(path).map_err(|e| e.into())
+        std::fs::create_dir_all(path).map_err(|e| AssetIoError::from_io(e, path))
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn file_metadata(&self, path: &Path) -> Result<FileMetadata, AssetIoError> {
+        let metadata = std::fs::metadata(path).map_err(|e| AssetIoError::from_io(e, path))?;
+        let modified = metadata
+            .modified()
+            .map_err(|e| AssetIoError::from_io(e, path))?;
+        Ok(FileMetadata::new(
+            metadata.len(),
+            modified,
+            metadata.is_dir(),
+        ))
+    }
+}
+
+/// Writes to a temp file and moves it over the destination on drop.
+struct LocalWriter {
+    file: Option<std::fs::File>,
+    temp: PathBuf,
+    path: PathBuf,
+}
+
+impl Write for LocalWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match &mut self.file {
+            Some(file) => file.write(buf),
+            None => Err(std::io::ErrorKind::BrokenPipe.into()),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match &mut self.file {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for LocalWriter {
+    fn drop(&mut self) {
+        let flushed = self.file.take().map(|mut file| file.flush());
+        if !matches!(flushed, Some(Ok(()))) || std::fs::rename(&self.temp, &self.path).is_err() {
+            let _ = std::fs::remove_file(&self.temp);
+        }
     }
 }
 
@@ -216,6 +454,10 @@ impl AssetFileSystem {
         self.system.write(path.as_ref(), data.as_ref())
     }
 
+    pub fn writer(&self, path: impl AsRef<Path>) -> Result<FileWriter, AssetIoError> {
+        self.system.writer(path.as_ref())
+    }
+
     pub fn remove(&self, path: impl AsRef<Path>) -> Result<Vec<PathBuf>, AssetIoError> {
         self.system.remove(path.as_ref())
     }
@@ -232,14 +474,104 @@ impl AssetFileSystem {
         self.system.read_directory(path.as_ref(), recursive)
     }
 
+    /// Reads a directory, keeping only the paths `filter` accepts.
+    pub fn read_directory_filtered(
+        &self,
+        path: impl AsRef<Path>,
+        recursive: bool,
+        filter: impl Fn(&Path) -> bool,
+    ) -> Result<Vec<PathBuf>, AssetIoError> {
+        let paths = self.read_directory(path, recursive)?;
+        Ok(paths.into_iter().filter(|path| filter(path)).collect())
+    }
+
+    /// Reads a directory, keeping only visible paths that match one of `patterns`.
+    pub fn read_directory_matching(
+        &self,
+        path: impl AsRef<Path>,
+        recursive: bool,
+        patterns: &[Glob],
+    ) -> Result<Vec<PathBuf>, AssetIoError> {
+        self.read_directory_filtered(path, recursive, |path| {
+            !path.is_hidden() && patterns.iter().any(|pattern| pattern.matches(path))
+        })
+    }
+
+    /// Reads the assets directory's ignore file, treating a missing one as empty.
+    pub fn load_ignore(&self) -> IgnoreList {
+        self.read_to_string(self.config.ignore())
+            .map(|content| IgnoreList::parse(&content))
+            .unwrap_or_default()
+    }
+
+    pub fn create_dir(&self, path: impl AsRef<Path>) -> Result<(), AssetIoError> {
+        self.system.create_dir(path.as_ref())
+    }
+
+    pub fn exists(&self, path: impl AsRef<Path>) -> bool {
+        self.system.exists(path.as_ref())
+    }
+
+    pub fn file_metadata(&self, path: impl AsRef<Path>) -> Result<FileMetadata, AssetIoError> {
+        self.system.file_metadata(path.as_ref())
+    }
+
     pub fn load_metadata<S: Settings>(
         &self,
         path: impl AsRef<Path>,
     ) -> Result<AssetMetadata<S>, AssetIoError> {
         let path = path.as_ref().append_extension("meta");
         let content = self.read_to_string(path)?;
-        toml::from_str::<AssetMetadata<S>>(&content)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e).into())
+        self.config
+            .metadata_format()
+            .deserialize::<AssetMetadata<S>>(&content)
+            .map_err(AssetIoError::InvalidData)
+    }
+
+    /// Reads the importer name pinned in an asset's metadata, if any, without
+    /// knowing its settings type.
+    pub fn load_metadata_importer(&self, path: impl AsRef<Path>) -> Option<String> {
+        let path = path.as_ref().append_extension("meta");
+        let content = self.read_to_string(path).ok()?;
+        // Metadata writes the importer as a plain string when present, which RON
+        // would not accept as an `Option`, so an absent field is read as empty.
+        #[derive(serde::Deserialize)]
+        struct PinnedImporter {
+            #[serde(default)]
+            importer: String,
+        }
+
+        let pinned = self
+            .config
+            .metadata_format()
+            .deserialize::<PinnedImporter>(&content)
+            .ok()?;
+        Some(pinned.importer).filter(|importer| !importer.is_empty())
+    }
+
+    /// Resolves the id of the asset at `path`: the one in its metadata if that can
+    /// be read, otherwise the id derived from its path under the assets directory.
+    pub fn asset_id(&self, path: impl AsRef<Path>) -> AssetId {
+        let path = path.as_ref();
+        self.load_metadata_id(path).unwrap_or_else(|_| {
+            let relative = path.strip_prefix(self.config.assets()).unwrap_or(path);
+            AssetId::from_path(relative)
+        })
+    }
+
+    /// Reads only the id from an asset's metadata, without knowing its settings type.
+    pub fn load_metadata_id(&self, path: impl AsRef<Path>) -> Result<AssetId, AssetIoError> {
+        #[derive(serde::Deserialize)]
+        struct MetadataId {
+            id: AssetId,
+        }
+
+        let content = self.read_to_string(path.as_ref().append_extension("meta"))?;
+        self.config
+            .metadata_format()
+            .deserialize::<MetadataId>(&content)
+            .map(|metadata| metadata.id)
+            .map_err(AssetIoError::InvalidData)
     }
 
     pub fn save_metadata<S: Settings>(
@@ -248,52 +580,492 @@ impl AssetFileSystem {
         metadata: &AssetMetadata<S>,
     ) -> Result<Vec<u8>, AssetIoError> {
         let path = path.as_ref().append_extension("meta");
-        let content = toml::to_string(metadata)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        let content = self
+            .config
+            .metadata_format()
+            .serialize(metadata)
+            .map_err(AssetIoError::InvalidData)?;
         self.write(path, &content)?;
         Ok(content.into())
     }
 
     pub fn load_artifact_meta(&self, id: &AssetId) -> Result<ArtifactMeta, AssetIoError> {
-        let path = self.config.artifact(id);
+        self.read_artifact_meta(id).map(|(meta, _)| meta)
+    }
+
+    /// Opens an artifact and reads up to the end of its meta, leaving the reader at
+    /// the payload header.
+    fn read_artifact_meta(&self, id: &AssetId) -> Result<(ArtifactMeta, FileReader), AssetIoError> {
+        let path = self.artifact_path(id);
         let mut reader = self.reader(&path)?;
+        let mut header = [0u8; ARTIFACT_HEADER_LEN];
+        if reader.read_exact(&mut header).is_err() || !Artifact::is_valid_header(&header) {
+            return Err(AssetIoError::InvalidHeader(path));
+        }
+
         let mut len_buffer = [0u8; 8];
         reader.read_exact(&mut len_buffer)?;
-        let len = usize::from_bytes(&len_buffer).ok_or(std::io::Error::new(
-            std::io::ErrorKind::InvalidData,
-            "Could not read length.",
-        ))?;
+        let len = usize::from_bytes(&len_buffer)
+            .ok_or(AssetIoError::InvalidData("Could not read length.".into()))?;
         let mut buffer = vec![0u8; len];
         reader.read_exact(&mut buffer)?;
-        let meta = ArtifactMeta::from_bytes(&buffer).ok_or(std::io::Error::new(
-            std::io::ErrorKind::InvalidData,
-            "Could not read artifact meta.",
+        let meta = ArtifactMeta::from_bytes(&buffer).ok_or(AssetIoError::InvalidData(
+            "Could not read artifact meta.".into(),
+        ))?;
+
+        Ok((meta, reader))
+    }
+
+    /// Loads an artifact's meta and returns a reader over its asset bytes, so large
+    /// payloads can be consumed without holding the whole file in memory.
+    pub fn load_artifact_streaming(&self, id: &AssetId) -> Result<ArtifactReader, AssetIoError> {
+        let (meta, mut reader) = self.read_artifact_meta(id)?;
+        let mut header = [0u8; 5];
+        reader.read_exact(&mut header).map_err(|_| {
+            let path = self.artifact_path(id);
+            AssetIoError::InvalidData(format!("Truncated artifact payload header: {:?}", path))
+        })?;
+        let compression = ArtifactCompression::from_tag(header[0]).ok_or(
+            AssetIoError::InvalidData("Unknown artifact compression.".into()),
+        )?;
+        let payload_checksum = u32::from_bytes(&header[1..]).ok_or(AssetIoError::InvalidData(
+            "Could not read payload checksum.".into(),
         ))?;
 
-        Ok(meta)
+        Ok(ArtifactReader::new(
+            meta,
+            compression,
+            payload_checksum,
+            reader,
+        ))
     }
 
     pub fn load_artifact(&self, id: &AssetId) -> Result<Artifact, AssetIoError> {
-        let path = self.config.artifact(id);
+        let path = self.artifact_path(id);
         let bytes = self.read(&path)?;
-        Artifact::from_bytes(&bytes).ok_or(
-            std::io::Error::new(std::io::ErrorKind::InvalidData, "Could not read artifact.").into(),
-        )
+        if !Artifact::is_valid_header(&bytes) {
+            return Err(AssetIoError::InvalidHeader(path));
+        }
+
+        Artifact::from_bytes(&bytes)
+            .ok_or(AssetIoError::InvalidData("Could not read artifact.".into()))
+    }
+
+    /// Loads an artifact and checks its asset bytes against the recorded checksum.
+    pub fn load_artifact_verified(&self, id: &AssetId) -> Result<Artifact, AssetIoError> {
+        let artifact = self.load_artifact(id)?;
+        match artifact.verify_checksum() {
+            true => Ok(artifact),
+            false => Err(AssetIoError::ChecksumMismatch(self.artifact_path(id))),
+        }
+    }
+
+    /// Resolves where an artifact is stored, falling back to the legacy unsharded
+    /// location so existing caches stay readable.
+    pub fn artifact_path(&self, id: &AssetId) -> PathBuf {
+        let path = self.config.artifact(id);
+        let legacy = self.config.legacy_artifact(id);
+        if !self.exists(&path) && self.exists(&legacy) {
+            legacy
+        } else {
+            path
+        }
+    }
+
+    pub fn save_artifact(&self, artifact: &Artifact) -> Result<(), AssetIoError> {
+        let path = self.config.artifact(&artifact.meta().id());
+        if let Some(parent) = path.parent() {
+            self.create_dir(parent)?;
+        }
+
+        self.write(&path, artifact.into_bytes())
+    }
+
+    /// Seconds since the epoch that `path` was last modified, read through the
+    /// underlying file system.
+    pub fn modified_secs(&self, path: impl AsRef<Path>) -> Result<u64, AssetIoError> {
+        let modified = self.file_metadata(path)?.modified();
+        let since_epoch = modified
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_err(|e| AssetIoError::InvalidData(e.to_string()))?;
+        Ok(since_epoch.as_secs())
+    }
+
+    /// Hashes an asset's source and metadata with the configured algorithm.
+    pub fn calculate_checksum(&self, asset: &[u8], metadata: &[u8]) -> Checksum {
+        self.config.hash_algorithm().hash(&[asset, metadata])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset::{config::MetadataFormat, database::tests::TextSettings};
+    use crate::asset::{AssetType, SettingsType};
+    use memory::MemoryFileSystem;
+    use std::io::ErrorKind;
+
+    #[test]
+    fn io_errors_map_to_explicit_variants() {
+        let map = |kind: ErrorKind| AssetIoError::from_io(kind.into(), "a.txt");
+        assert_eq!(map(ErrorKind::Unsupported), AssetIoError::Unsupported);
+        assert!(matches!(
+            map(ErrorKind::InvalidData),
+            AssetIoError::InvalidData(_)
+        ));
+        assert!(matches!(map(ErrorKind::Interrupted), AssetIoError::Io(_)));
+    }
+
+    #[test]
+    fn from_io_keeps_the_path() {
+        let path = Path::new("assets/a.txt");
+        let denied = std::io::Error::from(ErrorKind::PermissionDenied);
+        assert_eq!(
+            AssetIoError::from_io(denied, path),
+            AssetIoError::PermissionDenied(path.to_path_buf())
+        );
+        let missing = std::io::Error::from(ErrorKind::NotFound);
+        assert_eq!(
+            AssetIoError::from_io(missing, path),
+            AssetIoError::NotFound(path.to_path_buf())
+        );
+    }
+
+    #[test]
+    fn local_read_reports_missing_path() {
+        let path = std::env::temp_dir().join("gob-missing").join("a.txt");
+        let error = LocalFileSystem.read(&path).unwrap_err();
+        assert_eq!(error, AssetIoError::NotFound(path));
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("gob-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn interrupted_write_leaves_original_intact() {
+        let dir = temp_dir("interrupted-write");
+        let path = dir.join("a.txt");
+        LocalFileSystem.write(&path, b"original").unwrap();
+
+        // A crash between writing the temp file and renaming it.
+        let temp = LocalFileSystem::temp_path(&path);
+        std::fs::write(&temp, b"partial").unwrap();
+
+        assert_eq!(LocalFileSystem.read(&path).unwrap(), b"original");
+        assert_eq!(LocalFileSystem.read_directory(&dir, true).unwrap(), [path]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn write_keeps_unrelated_tmp_files() {
+        let dir = temp_dir("tmp-sibling");
+        let path = dir.join("a.txt");
+        let sibling = dir.join("a.txt.tmp");
+        std::fs::write(&sibling, b"keep").unwrap();
+
+        LocalFileSystem.write(&path, b"data").unwrap();
+        assert_eq!(LocalFileSystem.read(&sibling).unwrap(), b"keep");
+        assert_eq!(LocalFileSystem.read(&path).unwrap(), b"data");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn read_directory_lists_files() {
+        let dir = temp_dir("read-directory");
+        std::fs::create_dir(dir.join("nested")).unwrap();
+        for path in ["a.txt", "b.txt", "nested/c.txt"] {
+            std::fs::write(dir.join(path), path).unwrap();
+        }
+
+        let mut all = LocalFileSystem.read_directory(&dir, true).unwrap();
+        all.sort();
+        assert_eq!(
+            all,
+            [
+                dir.join("a.txt"),
+                dir.join("b.txt"),
+                dir.join("nested/c.txt")
+            ]
+        );
+
+        let mut top = LocalFileSystem.read_directory(&dir, false).unwrap();
+        top.sort();
+        assert_eq!(top, [dir.join("a.txt"), dir.join("b.txt")]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn read_directory_honors_recursive() {
+        let dir = temp_dir("recursive");
+        std::fs::create_dir_all(dir.join("one/two")).unwrap();
+        std::fs::write(dir.join("root.txt"), "").unwrap();
+        std::fs::write(dir.join("one/a.txt"), "").unwrap();
+        std::fs::write(dir.join("one/two/b.txt"), "").unwrap();
+
+        assert_eq!(
+            LocalFileSystem.read_directory(&dir, false).unwrap().len(),
+            1
+        );
+        assert_eq!(LocalFileSystem.read_directory(&dir, true).unwrap().len(), 3);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn writer_streams_to_disk() {
+        let dir = temp_dir("writer");
+        let path = dir.join("stream.bin");
+        let mut writer = LocalFileSystem.writer(&path).unwrap();
+        for chunk in [&b"gob "[..], b"streams ", b"bytes"] {
+            writer.write_all(chunk).unwrap();
+        }
+        writer.flush().unwrap();
+        drop(writer);
+
+        let mut reader = LocalFileSystem.reader(&path).unwrap();
+        assert_eq!(reader.read_to_end().unwrap(), b"gob streams bytes");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn writer_replaces_the_file_when_dropped() {
+        let dir = temp_dir("writer_atomic");
+        let path = dir.join("stream.bin");
+        std::fs::write(&path, "old").unwrap();
+
+        let mut writer = LocalFileSystem.writer(&path).unwrap();
+        writer.write_all(b"new").unwrap();
+        writer.flush().unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"old");
+
+        drop(writer);
+        assert_eq!(std::fs::read(&path).unwrap(), b"new");
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn metadata_reports_files_and_directories() {
+        let dir = temp_dir("metadata");
+        let path = dir.join("a.txt");
+        assert!(!LocalFileSystem.exists(&path));
+        std::fs::write(&path, "four").unwrap();
+
+        assert!(LocalFileSystem.exists(&path));
+        let file = LocalFileSystem.file_metadata(&path).unwrap();
+        assert_eq!((file.len(), file.is_dir()), (4, false));
+        assert!(LocalFileSystem.file_metadata(&dir).unwrap().is_dir());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn modified_secs_is_a_stable_timestamp() {
+        let dir = temp_dir("modified");
+        let fs = AssetFileSystem::new(AssetConfig::new(&dir), LocalFileSystem);
+        let path = dir.join("a.txt");
+        fs.write(&path, "a").unwrap();
+
+        let modified = fs.modified_secs(&path).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(1100));
+        assert_eq!(fs.modified_secs(&path).unwrap(), modified);
+        assert!(modified > 1_600_000_000);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn checksum_matches_crc32_check_value() {
+        let fs = AssetFileSystem::new(AssetConfig::new("project"), MemoryFileSystem::new());
+        let checksum = fs.calculate_checksum(b"12345", b"6789");
+        assert_eq!(checksum, Checksum::Crc32(0xcbf4_3926));
+    }
+
+    fn artifact_fs() -> AssetFileSystem {
+        AssetFileSystem::new(AssetConfig::new("project"), MemoryFileSystem::new())
+    }
+
+    fn artifact(asset: Vec<u8>) -> Artifact {
+        let meta = ArtifactMeta::new(
+            AssetId::gen(),
+            AssetType::dynamic(1),
+            SettingsType::dynamic(2),
+            Checksum::Crc32(0),
+            0,
+            Default::default(),
+        );
+        Artifact::new(meta, asset)
+    }
+
+    #[test]
+    fn bad_artifact_magic_is_rejected() {
+        let fs = artifact_fs();
+        let artifact = artifact(b"payload".to_vec());
+        let id = artifact.meta().id();
+        fs.save_artifact(&artifact).unwrap();
+        assert_eq!(fs.load_artifact(&id).unwrap().asset(), b"payload");
+
+        let mut bytes = fs.read(fs.artifact_path(&id)).unwrap();
+        bytes[..4].copy_from_slice(b"NOPE");
+        fs.write(fs.artifact_path(&id), &bytes).unwrap();
+
+        let error = fs.load_artifact(&id).err().unwrap();
+        assert_eq!(error, AssetIoError::InvalidHeader(fs.artifact_path(&id)));
+        assert!(error.to_string().contains("bad magic"));
+        assert!(matches!(
+            fs.load_artifact_meta(&id),
+            Err(AssetIoError::InvalidHeader(_))
+        ));
+
+        let path = temp_dir("bad-magic").join("artifact");
+        std::fs::write(&path, &bytes).unwrap();
+        assert_eq!(
+            Artifact::read_meta(&path),
+            Err(AssetIoError::InvalidHeader(path.clone()))
+        );
+    }
+
+    #[test]
+    fn read_meta_rejects_lengths_past_the_end_of_the_file() {
+        let artifact = artifact(b"bytes".to_vec());
+        let dir = temp_dir("read-meta");
+        let path = dir.join("artifact");
+        let mut bytes = artifact.into_bytes();
+        std::fs::write(&path, &bytes).unwrap();
+        assert_eq!(Artifact::read_meta(&path).as_ref(), Ok(artifact.meta()));
+
+        let len = ARTIFACT_HEADER_LEN..ARTIFACT_HEADER_LEN + 8;
+        bytes[len].copy_from_slice(&u64::MAX.to_le_bytes());
+        std::fs::write(&path, &bytes).unwrap();
+        assert!(matches!(
+            Artifact::read_meta(&path),
+            Err(AssetIoError::InvalidData(_))
+        ));
+        assert_eq!(
+            Artifact::read_meta(&dir.join("missing")),
+            Err(AssetIoError::NotFound(dir.join("missing")))
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn corrupted_artifact_fails_verification() {
+        let fs = artifact_fs();
+        let artifact = artifact(b"some asset bytes".to_vec());
+        let id = artifact.meta().id();
+        fs.save_artifact(&artifact).unwrap();
+        assert!(fs.load_artifact_verified(&id).is_ok());
+
+        let path = fs.artifact_path(&id);
+        let mut bytes = fs.read(&path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        fs.write(&path, &bytes).unwrap();
+
+        assert!(fs.load_artifact(&id).is_ok());
+        assert_eq!(
+            fs.load_artifact_verified(&id).err(),
+            Some(AssetIoError::ChecksumMismatch(path))
+        );
+    }
+
+    #[test]
+    fn metadata_round_trips_in_every_format() {
+        for format in [
+            MetadataFormat::Toml,
+            MetadataFormat::Json,
+            MetadataFormat::Ron,
+        ] {
+            let config = AssetConfig::builder("project")
+                .metadata_format(format)
+                .build();
+            let fs = AssetFileSystem::new(config, MemoryFileSystem::new());
+            let path = fs.config().assets().join("a.txt");
+            let metadata = AssetMetadata::new(AssetId::gen(), TextSettings { upper: true })
+                .with_importer("text")
+                .with_name("A")
+                .with_tags(["one", "two"]);
+            fs.save_metadata(&path, &metadata).unwrap();
+
+            let loaded = fs.load_metadata::<TextSettings>(&path).unwrap();
+            assert_eq!(loaded.id(), metadata.id(), "{:?}", format);
+            assert_eq!(loaded.importer(), Some("text"));
+            assert_eq!(loaded.name(), Some("A"));
+            assert_eq!(loaded.tags(), ["one", "two"]);
+            assert!(loaded.settings().upper);
+            assert_eq!(fs.load_metadata_id(&path), Ok(metadata.id()));
+            assert_eq!(fs.load_metadata_importer(&path).as_deref(), Some("text"));
+        }
     }
 
-    pub fn modified_secs(path: impl AsRef<Path>) -> Result<u64, AssetIoError> {
-        let metadata = path.as_ref().metadata()?;
-        let modified = metadata.modified().unwrap_or(SystemTime::now());
-        let elapsed = modified
-            .elapsed()
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
-        Ok(elapsed.as_secs())
+    #[test]
+    fn read_directory_matching_keeps_matching_files() {
+        let fs = artifact_fs();
+        let assets = fs.config().assets().to_path_buf();
+        for name in ["a.txt", "b.png", "notes/c.txt", ".hidden.txt", "d.txt.meta"] {
+            fs.write(assets.join(name), "").unwrap();
+        }
+
+        let mut paths = fs
+            .read_directory_matching(&assets, true, &[Glob::new("*.txt")])
+            .unwrap();
+        paths.sort();
+        assert_eq!(paths, [assets.join("a.txt"), assets.join("notes/c.txt")]);
+
+        let paths = fs
+            .read_directory_matching(&assets, true, &[Glob::new("notes/*.txt")])
+            .unwrap();
+        assert_eq!(paths, [assets.join("notes/c.txt")]);
     }
 
-    pub fn calculate_checksum(asset: &[u8], metadata: &[u8]) -> u32 {
-        let mut hasher = crc32fast::Hasher::new();
-        asset.hash(&mut hasher);
-        metadata.hash(&mut hasher);
-        hasher.finalize()
+    #[test]
+    fn streamed_artifacts_match_eager_loads() {
+        let fs = artifact_fs();
+        let asset = (0..10_000).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+        for compression in [ArtifactCompression::None, ArtifactCompression::Deflate] {
+            let artifact = artifact(asset.clone()).with_compression(compression);
+            let id = artifact.meta().id();
+            fs.save_artifact(&artifact).unwrap();
+
+            let eager = fs.load_artifact(&id).unwrap();
+            let mut streamed = fs.load_artifact_streaming(&id).unwrap();
+            assert_eq!(streamed.meta(), eager.meta());
+            assert_eq!(streamed.payload_checksum(), eager.payload_checksum());
+
+            let mut head = [0u8; 16];
+            streamed.reader().read_exact(&mut head).unwrap();
+            assert_eq!(head, eager.asset()[..16]);
+
+            let streamed = fs.load_artifact_streaming(&id).unwrap();
+            let loaded = streamed.into_artifact().unwrap();
+            assert_eq!(loaded.asset(), eager.asset());
+            assert_eq!(loaded.payload_checksum(), eager.payload_checksum());
+        }
+    }
+
+    #[test]
+    fn streamed_artifacts_check_the_recorded_checksum() {
+        let fs = artifact_fs();
+        let artifact = artifact(b"some asset bytes".to_vec());
+        let id = artifact.meta().id();
+        let path = fs.artifact_path(&id);
+        let mut bytes = artifact.into_bytes();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        fs.write(&path, &bytes).unwrap();
+
+        let streamed = fs.load_artifact_streaming(&id).unwrap();
+        assert_eq!(
+            streamed.into_artifact().err(),
+            Some(AssetIoError::ChecksumMismatch(path.clone()))
+        );
+
+        let meta_end = bytes.len() - b"some asset bytes".len() - 5;
+        fs.write(&path, &bytes[..meta_end + 2]).unwrap();
+        assert!(matches!(
+            fs.load_artifact_streaming(&id).err(),
+            Some(AssetIoError::InvalidData(message)) if message.contains("artifact")
+        ));
     }
 }
@@ -0,0 +1,196 @@
+use super::{AssetIoError, FileMetadata, FileReader, FileSystem, FileWriter};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+/// Stacks file systems so upper layers shadow lower ones. Layers are ordered from
+/// the top down: reads return the first layer that has the file and writes go to
+/// the topmost writable layer. Removes and renames go to the layer that holds the file.
+pub struct OverlayFileSystem {
+    layers: Vec<Box<dyn FileSystem>>,
+}
+
+impl OverlayFileSystem {
+    pub fn new() -> Self {
+        Self { layers: Vec::new() }
+    }
+
+    /// Adds a layer beneath the existing ones.
+    pub fn with_layer(mut self, layer: impl FileSystem) -> Self {
+        self.layers.push(Box::new(layer));
+        self
+    }
+
+    pub fn add_layer(&mut self, layer: impl FileSystem) -> &mut Self {
+        self.layers.push(Box::new(layer));
+        self
+    }
+
+    pub fn layers(&self) -> &[Box<dyn FileSystem>] {
+        &self.layers
+    }
+
+    fn find(&self, path: &Path) -> Result<&dyn FileSystem, AssetIoError> {
+        self.layers
+            .iter()
+            .find(|layer| layer.exists(path))
+            .map(|layer| layer.as_ref())
+            .ok_or_else(|| AssetIoError::NotFound(path.to_path_buf()))
+    }
+
+    /// Returns the layer that holds `path`, failing if that layer can't be modified.
+    fn owner(&self, path: &Path) -> Result<&dyn FileSystem, AssetIoError> {
+        match self.find(path)? {
+            layer if layer.is_read_only() => {
+                Err(AssetIoError::PermissionDenied(path.to_path_buf()))
+            }
+            layer => Ok(layer),
+        }
+    }
+
+    fn writable(&self, path: &Path) -> Result<&dyn FileSystem, AssetIoError> {
+        self.layers
+            .iter()
+            .find(|layer| !layer.is_read_only())
+            .map(|layer| layer.as_ref())
+            .ok_or_else(|| AssetIoError::PermissionDenied(path.to_path_buf()))
+    }
+}
+
+impl Default for OverlayFileSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FileSystem for OverlayFileSystem {
+    fn read(&self, path: &Path) -> Result<Vec<u8>, AssetIoError> {
+        self.find(path)?.read(path)
+    }
+
+    fn read_to_string(&self, path: &Path) -> Result<String, AssetIoError> {
+        self.find(path)?.read_to_string(path)
+    }
+
+    fn read_exact(&self, path: &Path, buffer: &mut [u8]) -> Result<(), AssetIoError> {
+        self.find(path)?.read_exact(path, buffer)
+    }
+
+    fn reader(&self, path: &Path) -> Result<FileReader, AssetIoError> {
+        self.find(path)?.reader(path)
+    }
+
+    fn write(&self, path: &Path, data: &[u8]) -> Result<(), AssetIoError> {
+        self.writable(path)?.write(path, data)
+    }
+
+    fn writer(&self, path: &Path) -> Result<FileWriter, AssetIoError> {
+        self.writable(path)?.writer(path)
+    }
+
+    fn remove(&self, path: &Path) -> Result<Vec<PathBuf>, AssetIoError> {
+        self.owner(path)?.remove(path)
+    }
+
+    fn rename(&self, old: &Path, new: &Path) -> Result<(), AssetIoError> {
+        self.owner(old)?.rename(old, new)
+    }
+
+    fn read_directory(&self, path: &Path, recursive: bool) -> Result<Vec<PathBuf>, AssetIoError> {
+        let mut seen = HashSet::new();
+        let mut entries = Vec::new();
+        let mut error = None;
+        let mut found = false;
+        for layer in &self.layers {
+            match layer.read_directory(path, recursive) {
+                Ok(paths) => {
+                    found = true;
+                    entries.extend(paths.into_iter().filter(|p| seen.insert(p.clone())));
+                }
+                Err(e) => error = error.or(Some(e)),
+            }
+        }
+
+        match error {
+            Some(error) if !found => Err(error),
+            _ => Ok(entries),
+        }
+    }
+
+    fn create_dir(&self, path: &Path) -> Result<(), AssetIoError> {
+        self.writable(path)?.create_dir(path)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.layers.iter().any(|layer| layer.exists(path))
+    }
+
+    fn file_metadata(&self, path: &Path) -> Result<FileMetadata, AssetIoError> {
+        self.find(path)?.file_metadata(path)
+    }
+
+    fn is_read_only(&self) -> bool {
+        self.layers.iter().all(|layer| layer.is_read_only())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset::io::{memory::MemoryFileSystem, readonly::ReadOnlyFileSystem};
+
+    #[test]
+    fn upper_layers_shadow_lower_ones() {
+        let upper = MemoryFileSystem::new();
+        let lower = MemoryFileSystem::new();
+        upper.write(Path::new("mods/a.txt"), b"modded").unwrap();
+        lower.write(Path::new("mods/a.txt"), b"base").unwrap();
+        lower.write(Path::new("mods/b.txt"), b"base").unwrap();
+
+        let fs = OverlayFileSystem::new()
+            .with_layer(upper.clone())
+            .with_layer(ReadOnlyFileSystem::new(lower.clone()));
+        assert_eq!(fs.read(Path::new("mods/a.txt")).unwrap(), b"modded");
+        assert_eq!(fs.read(Path::new("mods/b.txt")).unwrap(), b"base");
+
+        let mut entries = fs.read_directory(Path::new("mods"), false).unwrap();
+        entries.sort();
+        assert_eq!(entries, [Path::new("mods/a.txt"), Path::new("mods/b.txt")]);
+
+        fs.write(Path::new("mods/b.txt"), b"patched").unwrap();
+        assert_eq!(fs.read(Path::new("mods/b.txt")).unwrap(), b"patched");
+        assert_eq!(lower.read(Path::new("mods/b.txt")).unwrap(), b"base");
+    }
+
+    #[test]
+    fn remove_and_rename_target_the_layer_holding_the_file() {
+        let upper = MemoryFileSystem::new();
+        let middle = MemoryFileSystem::new();
+        let lower = MemoryFileSystem::new();
+        middle.write(Path::new("a.txt"), b"a").unwrap();
+        middle.write(Path::new("b.txt"), b"b").unwrap();
+        lower.write(Path::new("c.txt"), b"c").unwrap();
+
+        let fs = OverlayFileSystem::new()
+            .with_layer(upper.clone())
+            .with_layer(middle.clone())
+            .with_layer(ReadOnlyFileSystem::new(lower.clone()));
+        fs.rename(Path::new("a.txt"), Path::new("d.txt")).unwrap();
+        assert_eq!(middle.read(Path::new("d.txt")).unwrap(), b"a");
+        assert!(!upper.exists(Path::new("d.txt")));
+
+        fs.remove(Path::new("b.txt")).unwrap();
+        assert!(!fs.exists(Path::new("b.txt")));
+
+        assert!(matches!(
+            fs.remove(Path::new("c.txt")),
+            Err(AssetIoError::PermissionDenied(_))
+        ));
+        assert!(matches!(
+            fs.rename(Path::new("c.txt"), Path::new("e.txt")),
+            Err(AssetIoError::PermissionDenied(_))
+        ));
+        assert!(lower.exists(Path::new("c.txt")));
+    }
+}
@@ -0,0 +1,98 @@
+use super::{AssetIoError, FileMetadata, FileReader, FileSystem, FileWriter};
+use std::path::{Path, PathBuf};
+
+/// Forwards reads to an inner file system and rejects every mutating call.
+pub struct ReadOnlyFileSystem<F: FileSystem> {
+    inner: F,
+}
+
+impl<F: FileSystem> ReadOnlyFileSystem<F> {
+    pub fn new(inner: F) -> Self {
+        Self { inner }
+    }
+
+    pub fn inner(&self) -> &F {
+        &self.inner
+    }
+}
+
+impl<F: FileSystem> FileSystem for ReadOnlyFileSystem<F> {
+    fn read(&self, path: &Path) -> Result<Vec<u8>, AssetIoError> {
+        self.inner.read(path)
+    }
+
+    fn read_to_string(&self, path: &Path) -> Result<String, AssetIoError> {
+        self.inner.read_to_string(path)
+    }
+
+    fn read_exact(&self, path: &Path, buffer: &mut [u8]) -> Result<(), AssetIoError> {
+        self.inner.read_exact(path, buffer)
+    }
+
+    fn reader(&self, path: &Path) -> Result<FileReader, AssetIoError> {
+        self.inner.reader(path)
+    }
+
+    fn write(&self, path: &Path, _: &[u8]) -> Result<(), AssetIoError> {
+        Err(AssetIoError::PermissionDenied(path.to_path_buf()))
+    }
+
+    fn writer(&self, path: &Path) -> Result<FileWriter, AssetIoError> {
+        Err(AssetIoError::PermissionDenied(path.to_path_buf()))
+    }
+
+    fn remove(&self, path: &Path) -> Result<Vec<PathBuf>, AssetIoError> {
+        Err(AssetIoError::PermissionDenied(path.to_path_buf()))
+    }
+
+    fn rename(&self, old: &Path, _: &Path) -> Result<(), AssetIoError> {
+        Err(AssetIoError::PermissionDenied(old.to_path_buf()))
+    }
+
+    fn read_directory(&self, path: &Path, recursive: bool) -> Result<Vec<PathBuf>, AssetIoError> {
+        self.inner.read_directory(path, recursive)
+    }
+
+    fn create_dir(&self, path: &Path) -> Result<(), AssetIoError> {
+        Err(AssetIoError::PermissionDenied(path.to_path_buf()))
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.inner.exists(path)
+    }
+
+    fn file_metadata(&self, path: &Path) -> Result<FileMetadata, AssetIoError> {
+        self.inner.file_metadata(path)
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset::io::memory::MemoryFileSystem;
+
+    #[test]
+    fn reads_pass_through_and_writes_fail() {
+        let inner = MemoryFileSystem::new();
+        inner.write(Path::new("a.txt"), b"hello").unwrap();
+        let fs = ReadOnlyFileSystem::new(inner);
+        let path = Path::new("a.txt");
+
+        assert_eq!(fs.read(path).unwrap(), b"hello");
+        assert_eq!(fs.read_to_string(path).unwrap(), "hello");
+        assert!(fs.exists(path));
+        assert!(fs.is_read_only());
+
+        let denied = Err(AssetIoError::PermissionDenied(path.to_path_buf()));
+        assert_eq!(fs.write(path, b"changed"), denied);
+        assert!(fs.writer(path).is_err());
+        assert_eq!(fs.remove(path).map(|_| ()), denied);
+        assert!(fs.rename(path, Path::new("b.txt")).is_err());
+        assert!(fs.create_dir(Path::new("dir")).is_err());
+        assert_eq!(fs.read(path).unwrap(), b"hello");
+    }
+}
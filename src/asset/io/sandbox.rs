@@ -0,0 +1,185 @@
+use super::{AssetIoError, FileMetadata, FileReader, FileSystem, FileWriter};
+use std::path::{Component, Path, PathBuf};
+
+/// Confines every path to a root directory, rejecting anything that escapes it
+/// through `..` components or symlinks.
+pub struct SandboxedFileSystem<F: FileSystem> {
+    inner: F,
+    root: PathBuf,
+    canonical_root: PathBuf,
+}
+
+impl<F: FileSystem> SandboxedFileSystem<F> {
+    pub fn new(inner: F, root: impl AsRef<Path>) -> Self {
+        let root = normalize(root.as_ref());
+        let canonical_root = root.canonicalize().unwrap_or_else(|_| root.clone());
+
+        Self {
+            inner,
+            root,
+            canonical_root,
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn inner(&self) -> &F {
+        &self.inner
+    }
+
+    /// Resolves `path` against the root and checks that it stays inside it.
+    pub fn resolve(&self, path: &Path) -> Result<PathBuf, AssetIoError> {
+        let resolved = normalize(&self.root.join(path));
+        if !resolved.starts_with(&self.root)
+            || !canonical(&resolved).starts_with(&self.canonical_root)
+        {
+            return Err(AssetIoError::PermissionDenied(path.to_path_buf()));
+        }
+
+        Ok(resolved)
+    }
+
+    /// Maps paths reported by the inner file system back to the caller's form, so a
+    /// relative request gets relative results.
+    fn unresolve(&self, path: &Path, paths: Vec<PathBuf>) -> Vec<PathBuf> {
+        if path.is_absolute() {
+            return paths;
+        }
+
+        paths
+            .into_iter()
+            .map(|entry| match entry.strip_prefix(&self.root) {
+                Ok(relative) => relative.to_path_buf(),
+                Err(_) => entry,
+            })
+            .collect()
+    }
+}
+
+/// Lexically resolves `.` and `..` components without touching the file system.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+
+    normalized
+}
+
+/// Canonicalizes the longest existing ancestor of `path` so symlinks are followed
+/// even when the path itself has not been created yet.
+fn canonical(path: &Path) -> PathBuf {
+    let mut remainder = Vec::new();
+    let mut current = path;
+    loop {
+        if let Ok(canonical) = current.canonicalize() {
+            return remainder
+                .iter()
+                .rev()
+                .fold(canonical, |path, part| path.join(part));
+        }
+
+        match (current.parent(), current.file_name()) {
+            (Some(parent), Some(name)) => {
+                remainder.push(name.to_os_string());
+                current = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+impl<F: FileSystem> FileSystem for SandboxedFileSystem<F> {
+    fn read(&self, path: &Path) -> Result<Vec<u8>, AssetIoError> {
+        self.inner.read(&self.resolve(path)?)
+    }
+
+    fn read_to_string(&self, path: &Path) -> Result<String, AssetIoError> {
+        self.inner.read_to_string(&self.resolve(path)?)
+    }
+
+    fn read_exact(&self, path: &Path, buffer: &mut [u8]) -> Result<(), AssetIoError> {
+        self.inner.read_exact(&self.resolve(path)?, buffer)
+    }
+
+    fn reader(&self, path: &Path) -> Result<FileReader, AssetIoError> {
+        self.inner.reader(&self.resolve(path)?)
+    }
+
+    fn write(&self, path: &Path, data: &[u8]) -> Result<(), AssetIoError> {
+        self.inner.write(&self.resolve(path)?, data)
+    }
+
+    fn writer(&self, path: &Path) -> Result<FileWriter, AssetIoError> {
+        self.inner.writer(&self.resolve(path)?)
+    }
+
+    fn remove(&self, path: &Path) -> Result<Vec<PathBuf>, AssetIoError> {
+        let removed = self.inner.remove(&self.resolve(path)?)?;
+        Ok(self.unresolve(path, removed))
+    }
+
+    fn rename(&self, old: &Path, new: &Path) -> Result<(), AssetIoError> {
+        self.inner.rename(&self.resolve(old)?, &self.resolve(new)?)
+    }
+
+    fn read_directory(&self, path: &Path, recursive: bool) -> Result<Vec<PathBuf>, AssetIoError> {
+        let entries = self.inner.read_directory(&self.resolve(path)?, recursive)?;
+        Ok(self.unresolve(path, entries))
+    }
+
+    fn create_dir(&self, path: &Path) -> Result<(), AssetIoError> {
+        self.inner.create_dir(&self.resolve(path)?)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.resolve(path)
+            .map(|path| self.inner.exists(&path))
+            .unwrap_or(false)
+    }
+
+    fn file_metadata(&self, path: &Path) -> Result<FileMetadata, AssetIoError> {
+        self.inner.file_metadata(&self.resolve(path)?)
+    }
+
+    fn is_read_only(&self) -> bool {
+        self.inner.is_read_only()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset::io::memory::MemoryFileSystem;
+
+    #[test]
+    fn paths_cannot_escape_the_root() {
+        let inner = MemoryFileSystem::new();
+        inner.write(Path::new("secret.txt"), b"secret").unwrap();
+        let fs = SandboxedFileSystem::new(inner, "project");
+
+        fs.write(Path::new("assets/a.txt"), b"hello").unwrap();
+        assert_eq!(fs.read(Path::new("assets/a.txt")).unwrap(), b"hello");
+        assert_eq!(
+            fs.read(Path::new("assets/../assets/a.txt")).unwrap(),
+            b"hello"
+        );
+        assert!(fs.inner().exists(Path::new("project/assets/a.txt")));
+
+        let escape = Path::new("../secret.txt");
+        assert_eq!(
+            fs.read(escape),
+            Err(AssetIoError::PermissionDenied(escape.to_path_buf()))
+        );
+        assert!(fs.write(Path::new("assets/../../x.txt"), b"x").is_err());
+        assert!(!fs.exists(escape));
+    }
+}
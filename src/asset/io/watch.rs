@@ -0,0 +1,140 @@
+use super::{AssetIoError, FileMetadata, FileReader, FileSystem, FileWriter, LocalFileSystem};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        Arc, Mutex,
+    },
+};
+
+/// A local file system that reports created, modified, and removed files under a
+/// watched root.
+pub struct WatchedFileSystem {
+    inner: LocalFileSystem,
+    root: PathBuf,
+    subscribers: Arc<Mutex<Vec<Sender<PathBuf>>>>,
+    _watcher: Mutex<RecommendedWatcher>,
+}
+
+impl WatchedFileSystem {
+    pub fn new(root: impl AsRef<Path>) -> Result<Self, AssetIoError> {
+        let root = root.as_ref().to_path_buf();
+        let subscribers = Arc::new(Mutex::new(Vec::<Sender<PathBuf>>::new()));
+        let senders = subscribers.clone();
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(_) => return,
+                };
+
+                match event.kind {
+                    EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) => {
+                        let mut senders = senders.lock().unwrap();
+                        for path in event.paths {
+                            senders.retain(|sender| sender.send(path.clone()).is_ok());
+                        }
+                    }
+                    _ => {}
+                }
+            })
+            .map_err(|e| AssetIoError::from_io(std::io::Error::other(e), &root))?;
+
+        watcher
+            .watch(&root, RecursiveMode::Recursive)
+            .map_err(|e| AssetIoError::from_io(std::io::Error::other(e), &root))?;
+
+        Ok(Self {
+            inner: LocalFileSystem,
+            root,
+            subscribers,
+            _watcher: Mutex::new(watcher),
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Subscribes to change events. Every receiver gets each changed path.
+    pub fn changes(&self) -> Receiver<PathBuf> {
+        let (sender, receiver) = channel();
+        self.subscribers.lock().unwrap().push(sender);
+        receiver
+    }
+}
+
+impl FileSystem for WatchedFileSystem {
+    fn read(&self, path: &Path) -> Result<Vec<u8>, AssetIoError> {
+        self.inner.read(path)
+    }
+
+    fn read_to_string(&self, path: &Path) -> Result<String, AssetIoError> {
+        self.inner.read_to_string(path)
+    }
+
+    fn read_exact(&self, path: &Path, buffer: &mut [u8]) -> Result<(), AssetIoError> {
+        self.inner.read_exact(path, buffer)
+    }
+
+    fn reader(&self, path: &Path) -> Result<FileReader, AssetIoError> {
+        self.inner.reader(path)
+    }
+
+    fn write(&self, path: &Path, data: &[u8]) -> Result<(), AssetIoError> {
+        self.inner.write(path, data)
+    }
+
+    fn writer(&self, path: &Path) -> Result<FileWriter, AssetIoError> {
+        self.inner.writer(path)
+    }
+
+    fn remove(&self, path: &Path) -> Result<Vec<PathBuf>, AssetIoError> {
+        self.inner.remove(path)
+    }
+
+    fn rename(&self, old: &Path, new: &Path) -> Result<(), AssetIoError> {
+        self.inner.rename(old, new)
+    }
+
+    fn read_directory(&self, path: &Path, recursive: bool) -> Result<Vec<PathBuf>, AssetIoError> {
+        self.inner.read_directory(path, recursive)
+    }
+
+    fn create_dir(&self, path: &Path) -> Result<(), AssetIoError> {
+        self.inner.create_dir(path)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.inner.exists(path)
+    }
+
+    fn file_metadata(&self, path: &Path) -> Result<FileMetadata, AssetIoError> {
+        self.inner.file_metadata(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_arrive_as_changes() {
+        let root = std::env::temp_dir().join(format!("gob-watch-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+
+        let fs = WatchedFileSystem::new(&root).unwrap();
+        let changes = fs.changes();
+        fs.write(&root.join("a.txt"), b"hello").unwrap();
+
+        let timeout = std::time::Duration::from_secs(5);
+        let changed = std::iter::from_fn(|| changes.recv_timeout(timeout).ok())
+            .any(|path| path.file_name() == Some("a.txt".as_ref()));
+        assert!(changed);
+
+        drop(fs);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
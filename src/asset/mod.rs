@@ -1,8 +1,11 @@
+use crate::hash::type_hash;
 use bytes::IntoBytes;
 use serde::ser::SerializeStruct;
 use std::{
+    fmt::Display,
     hash::{Hash, Hasher},
     path::{Path, PathBuf},
+    str::FromStr,
 };
 
 pub mod artifact;
@@ -11,16 +14,21 @@ pub mod config;
 pub mod database;
 pub mod io;
 
-pub trait Asset: Send + Sync + 'static {}
+pub use gob_derive::Asset;
+
+pub trait Asset: Send + Sync + 'static {
+    /// The name diagnostics use for this asset type.
+    fn name() -> &'static str {
+        std::any::type_name::<Self>()
+    }
+}
 
 pub trait Settings:
     Default + Send + Sync + serde::Serialize + for<'a> serde::Deserialize<'a> + 'static
 {
 }
 
-#[derive(
-    Default, Clone, Copy, Debug, Eq, Hash, PartialEq, serde::Serialize, serde::Deserialize,
-)]
+#[derive(Default, Clone, Copy, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
 pub struct AssetId(u64);
 
 impl AssetId {
@@ -30,6 +38,29 @@ impl AssetId {
         id.hash(&mut hasher);
         AssetId(hasher.finish())
     }
+
+    /// Derives a stable id from a relative path. The path is normalized to `/`-separated
+    /// components and hashed with FNV-1a so the id is identical across runs and machines.
+    pub fn from_path(path: &Path) -> Self {
+        let mut components = Vec::new();
+        for component in path.components() {
+            match component {
+                std::path::Component::Normal(part) => components.push(part.to_string_lossy()),
+                std::path::Component::ParentDir => {
+                    components.pop();
+                }
+                _ => {}
+            }
+        }
+
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in components.join("/").bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+
+        AssetId(hash)
+    }
 }
 
 impl IntoBytes for AssetId {
@@ -42,56 +73,186 @@ impl IntoBytes for AssetId {
     }
 }
 
-impl ToString for AssetId {
-    fn to_string(&self) -> String {
-        self.0.to_string()
+impl Display for AssetId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl FromStr for AssetId {
+    type Err = ParseAssetIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != 16 || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(ParseAssetIdError(s.to_string()));
+        }
+
+        u64::from_str_radix(s, 16)
+            .map(AssetId)
+            .map_err(|_| ParseAssetIdError(s.to_string()))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseAssetIdError(String);
+
+impl Display for ParseAssetIdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid asset id {:?}: expected 16 hex digits", self.0)
+    }
+}
+
+impl std::error::Error for ParseAssetIdError {}
+
+impl serde::Serialize for AssetId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for AssetId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(AssetIdVisitor)
+    }
+}
+
+/// Reads hex string ids, and the plain integers that older metadata was written with.
+struct AssetIdVisitor;
+
+impl serde::de::Visitor<'_> for AssetIdVisitor {
+    type Value = AssetId;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("an asset id as 16 hex digits or an integer")
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<AssetId, E> {
+        Ok(AssetId(value))
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, value: i64) -> Result<AssetId, E> {
+        u64::try_from(value)
+            .map(AssetId)
+            .map_err(|_| E::invalid_value(serde::de::Unexpected::Signed(value), &self))
+    }
+
+    fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<AssetId, E> {
+        value.parse().map_err(E::custom)
     }
 }
 
 #[derive(Default, Clone, Copy, Debug, Eq, Hash, PartialEq)]
-pub struct AssetType(u64);
+pub struct AssetType(u128);
 
 impl AssetType {
     pub fn from<A: Asset>() -> Self {
-        let mut hasher = std::collections::hash_map::DefaultHasher::new();
-        std::any::TypeId::of::<A>().hash(&mut hasher);
-        AssetType(hasher.finish())
+        AssetType(type_hash::<A>())
     }
 
-    pub fn dynamic(ty: u64) -> Self {
+    pub fn dynamic(ty: u128) -> Self {
         AssetType(ty)
     }
 }
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-pub struct SettingsType(u64);
+impl IntoBytes for AssetType {
+    fn into_bytes(&self) -> Vec<u8> {
+        self.0.into_bytes()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        u128::from_bytes(bytes).map(AssetType)
+    }
+}
+
+#[derive(Default, Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct SettingsType(u128);
 
 impl SettingsType {
     pub fn from<S: Settings>() -> Self {
-        let mut hasher = std::collections::hash_map::DefaultHasher::new();
-        std::any::TypeId::of::<S>().hash(&mut hasher);
-        SettingsType(hasher.finish())
+        SettingsType(type_hash::<S>())
     }
 
-    pub fn dynamic(ty: u64) -> Self {
+    pub fn dynamic(ty: u128) -> Self {
         SettingsType(ty)
     }
 }
 
+impl IntoBytes for SettingsType {
+    fn into_bytes(&self) -> Vec<u8> {
+        self.0.into_bytes()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        u128::from_bytes(bytes).map(SettingsType)
+    }
+}
+
 pub struct AssetMetadata<S: Settings> {
     id: AssetId,
+    importer: Option<String>,
+    name: Option<String>,
+    tags: Vec<String>,
     settings: S,
 }
 
 impl<S: Settings> AssetMetadata<S> {
     pub fn new(id: AssetId, settings: S) -> Self {
-        AssetMetadata { id, settings }
+        AssetMetadata {
+            id,
+            importer: None,
+            name: None,
+            tags: Vec::new(),
+            settings,
+        }
+    }
+
+    /// Pins the asset to the importer with the given name when several importers
+    /// share its extension.
+    pub fn with_importer(mut self, importer: impl Into<String>) -> Self {
+        self.importer = Some(importer.into());
+        self
+    }
+
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn with_tags(mut self, tags: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.tags = tags.into_iter().map(Into::into).collect();
+        self
     }
 
     pub fn id(&self) -> AssetId {
         self.id
     }
 
+    pub fn importer(&self) -> Option<&str> {
+        self.importer.as_deref()
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn set_name(&mut self, name: Option<String>) {
+        self.name = name;
+    }
+
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    pub fn tags_mut(&mut self) -> &mut Vec<String> {
+        &mut self.tags
+    }
+
     pub fn settings(&self) -> &S {
         &self.settings
     }
@@ -105,6 +266,9 @@ impl<S: Settings> Default for AssetMetadata<S> {
     fn default() -> Self {
         AssetMetadata {
             id: AssetId::gen(),
+            importer: None,
+            name: None,
+            tags: Vec::new(),
             settings: S::default(),
         }
     }
@@ -115,8 +279,24 @@ impl<S: Settings> serde::Serialize for AssetMetadata<S> {
     where
         Ser: serde::Serializer,
     {
-        let mut state = serializer.serialize_struct("AssetMetadata", 2)?;
+        let len = 2
+            + self.importer.is_some() as usize
+            + self.name.is_some() as usize
+            + !self.tags.is_empty() as usize;
+        let mut state = serializer.serialize_struct("AssetMetadata", len)?;
         state.serialize_field("id", &self.id)?;
+        match &self.importer {
+            Some(importer) => state.serialize_field("importer", importer)?,
+            None => state.skip_field("importer")?,
+        }
+        match &self.name {
+            Some(name) => state.serialize_field("name", name)?,
+            None => state.skip_field("name")?,
+        }
+        match self.tags.is_empty() {
+            false => state.serialize_field("tags", &self.tags)?,
+            true => state.skip_field("tags")?,
+        }
         state.serialize_field("settings", &self.settings)?;
         state.end()
     }
@@ -129,6 +309,18 @@ impl<'de, S: Settings> serde::Deserialize<'de> for AssetMetadata<S> {
     {
         struct AssetMetadataVisitor<S: Settings>(std::marker::PhantomData<S>);
 
+        #[derive(serde::Deserialize)]
+        #[serde(field_identifier, rename_all = "lowercase")]
+        enum Field {
+            Id,
+            Importer,
+            Name,
+            Tags,
+            Settings,
+            #[serde(other)]
+            Other,
+        }
+
         impl<'de, S: Settings> serde::de::Visitor<'de> for AssetMetadataVisitor<S> {
             type Value = AssetMetadata<S>;
 
@@ -141,22 +333,43 @@ impl<'de, S: Settings> serde::Deserialize<'de> for AssetMetadata<S> {
                 A: serde::de::MapAccess<'de>,
             {
                 let mut id = None;
+                let mut importer = None;
+                let mut name = None;
+                let mut tags = None;
                 let mut settings = None;
-                while let Some(key) = map.next_key::<String>()? {
-                    match key.as_str() {
-                        "id" => {
+                while let Some(key) = map.next_key::<Field>()? {
+                    match key {
+                        Field::Id => {
                             if id.is_some() {
                                 return Err(serde::de::Error::duplicate_field("id"));
                             }
                             id = Some(map.next_value()?);
                         }
-                        "settings" => {
+                        Field::Importer => {
+                            if importer.is_some() {
+                                return Err(serde::de::Error::duplicate_field("importer"));
+                            }
+                            importer = Some(map.next_value()?);
+                        }
+                        Field::Name => {
+                            if name.is_some() {
+                                return Err(serde::de::Error::duplicate_field("name"));
+                            }
+                            name = Some(map.next_value()?);
+                        }
+                        Field::Tags => {
+                            if tags.is_some() {
+                                return Err(serde::de::Error::duplicate_field("tags"));
+                            }
+                            tags = Some(map.next_value()?);
+                        }
+                        Field::Settings => {
                             if settings.is_some() {
                                 return Err(serde::de::Error::duplicate_field("settings"));
                             }
                             settings = Some(map.next_value()?);
                         }
-                        _ => {
+                        Field::Other => {
                             map.next_value::<serde::de::IgnoredAny>()?;
                         }
                     }
@@ -164,13 +377,19 @@ impl<'de, S: Settings> serde::Deserialize<'de> for AssetMetadata<S> {
                 let id = id.ok_or_else(|| serde::de::Error::missing_field("id"))?;
                 let settings =
                     settings.ok_or_else(|| serde::de::Error::missing_field("settings"))?;
-                Ok(AssetMetadata { id, settings })
+                Ok(AssetMetadata {
+                    id,
+                    importer,
+                    name,
+                    tags: tags.unwrap_or_default(),
+                    settings,
+                })
             }
         }
 
         deserializer.deserialize_struct(
             "AssetMetadata",
-            &["id", "settings"],
+            &["id", "importer", "name", "tags", "settings"],
             AssetMetadataVisitor(Default::default()),
         )
     }
@@ -178,15 +397,165 @@ impl<'de, S: Settings> serde::Deserialize<'de> for AssetMetadata<S> {
 
 pub trait PathExt {
     fn append_extension(&self, ext: &str) -> PathBuf;
+    fn set_extension(&self, ext: &str) -> PathBuf;
     fn ext(&self) -> Option<&str>;
+    fn full_ext(&self) -> Option<&str>;
+    fn is_hidden(&self) -> bool;
 }
 
-impl<T: AsRef<Path>> PathExt for T {
+impl<T: AsRef<Path> + ?Sized> PathExt for T {
     fn append_extension(&self, ext: &str) -> PathBuf {
         PathBuf::from(format!("{}.{}", self.as_ref().display(), ext))
     }
 
+    fn set_extension(&self, ext: &str) -> PathBuf {
+        self.as_ref().with_extension(ext)
+    }
+
     fn ext(&self) -> Option<&str> {
         self.as_ref().extension().and_then(|ext| ext.to_str())
     }
+
+    /// Everything after the first dot of the file name, so `a.tar.gz` gives `tar.gz`.
+    /// A leading dot marks a hidden file rather than an extension.
+    fn full_ext(&self) -> Option<&str> {
+        let name = self.as_ref().file_name()?.to_str()?;
+        let name = name.strip_prefix('.').unwrap_or(name);
+        name.split_once('.')
+            .map(|(_, ext)| ext)
+            .filter(|ext| !ext.is_empty())
+    }
+
+    fn is_hidden(&self) -> bool {
+        self.as_ref()
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with('.'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset::bytes::IntoBytes;
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct Wrapper {
+        id: AssetId,
+    }
+
+    #[derive(Asset)]
+    #[asset(name = "sprite")]
+    struct Sprite;
+
+    #[test]
+    fn derived_asset_uses_its_name() {
+        assert_eq!(Sprite::name(), "sprite");
+    }
+
+    #[test]
+    fn asset_id_string_round_trips() {
+        for id in [AssetId::gen(), AssetId::default(), AssetId(u64::MAX)] {
+            assert_eq!(AssetId::from_str(&id.to_string()), Ok(id));
+        }
+
+        assert!(AssetId::from_str("not an id").is_err());
+        assert!(AssetId::from_str("0123").is_err());
+    }
+
+    #[test]
+    fn asset_id_deserializes_legacy_integers() {
+        let id = AssetId(12345678901234567890);
+        let hex = toml::to_string(&Wrapper { id }).unwrap();
+        assert_eq!(toml::from_str::<Wrapper>(&hex).unwrap().id, id);
+
+        assert_eq!(
+            toml::from_str::<Wrapper>("id = 42").unwrap().id,
+            AssetId(42)
+        );
+        let json = format!("{{\"id\": {}}}", id.0);
+        assert_eq!(serde_json::from_str::<Wrapper>(&json).unwrap().id, id);
+        assert!(toml::from_str::<Wrapper>("id = -1").is_err());
+        assert!(toml::from_str::<Wrapper>("id = \"12ab\"").is_err());
+    }
+
+    #[test]
+    fn type_ids_round_trip() {
+        let ty = AssetType::from::<Sprite>();
+        assert_eq!(AssetType::from_bytes(&ty.into_bytes()), Some(ty));
+
+        #[derive(Default, serde::Serialize, serde::Deserialize)]
+        struct SpriteSettings;
+        impl Settings for SpriteSettings {}
+
+        let settings = SettingsType::from::<SpriteSettings>();
+        assert_eq!(
+            SettingsType::from_bytes(&settings.into_bytes()),
+            Some(settings)
+        );
+        assert_eq!(AssetType::from_bytes(&[0; 15]), None);
+    }
+
+    #[test]
+    fn path_ids_are_deterministic() {
+        let id = AssetId::from_path(Path::new("textures/grass.png"));
+        assert_eq!(AssetId::from_path(Path::new("textures/grass.png")), id);
+        assert_eq!(
+            AssetId::from_path(Path::new("./textures/../textures/grass.png")),
+            id
+        );
+        assert_ne!(AssetId::from_path(Path::new("textures/dirt.png")), id);
+    }
+
+    #[derive(Default, serde::Serialize, serde::Deserialize)]
+    struct ScaleSettings {
+        scale: u32,
+    }
+
+    impl Settings for ScaleSettings {}
+
+    #[test]
+    fn metadata_reads_old_and_new_layouts() {
+        let old = "id = 255\n\n[settings]\nscale = 2\n";
+        let metadata = toml::from_str::<AssetMetadata<ScaleSettings>>(old).unwrap();
+        assert_eq!(metadata.id(), AssetId(0xff));
+        assert_eq!(metadata.settings().scale, 2);
+        assert_eq!(metadata.importer(), None);
+        assert_eq!(metadata.name(), None);
+        assert!(metadata.tags().is_empty());
+
+        let new = "id = \"00000000000000ff\"\nimporter = \"images\"\nname = \"Grass\"\n\
+                   tags = [\"terrain\", \"green\"]\n\n[settings]\nscale = 3\n";
+        let metadata = toml::from_str::<AssetMetadata<ScaleSettings>>(new).unwrap();
+        assert_eq!(metadata.importer(), Some("images"));
+        assert_eq!(metadata.name(), Some("Grass"));
+        assert_eq!(metadata.tags(), ["terrain", "green"]);
+        assert_eq!(metadata.settings().scale, 3);
+    }
+
+    #[test]
+    fn set_extension_replaces_the_last_extension() {
+        assert_eq!(
+            Path::new("a/foo.png").set_extension("gz"),
+            Path::new("a/foo.gz")
+        );
+        assert_eq!(
+            Path::new("a/foo").set_extension("gz"),
+            Path::new("a/foo.gz")
+        );
+        assert_eq!(
+            Path::new("a/foo.png").append_extension("meta"),
+            Path::new("a/foo.png.meta")
+        );
+    }
+
+    #[test]
+    fn full_ext_covers_compound_extensions() {
+        assert_eq!(Path::new("a.png").full_ext(), Some("png"));
+        assert_eq!(Path::new("a.tar.gz").full_ext(), Some("tar.gz"));
+        assert_eq!(Path::new("a.tar.gz").ext(), Some("gz"));
+        assert_eq!(Path::new(".hidden").full_ext(), None);
+        assert_eq!(Path::new(".hidden.txt").full_ext(), Some("txt"));
+        assert!(Path::new(".hidden").is_hidden());
+    }
 }
@@ -1,112 +1,417 @@
 use std::{
-    collections::{HashMap, VecDeque},
-    sync::{Arc, Mutex},
-    thread::{JoinHandle, ThreadId},
+    cmp::Ordering,
+    collections::{BinaryHeap, VecDeque},
+    sync::{mpsc::Receiver, Arc, Condvar, Mutex},
+    thread::JoinHandle,
 };
 
 pub type Task = Box<dyn FnOnce() + Send + 'static>;
 
+struct QueuedTask {
+    priority: u8,
+    sequence: u64,
+    task: Task,
+}
+
+impl PartialEq for QueuedTask {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for QueuedTask {}
+
+impl PartialOrd for QueuedTask {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueuedTask {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Higher priorities first, then earlier spawns within the same priority.
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
 pub struct TaskPoolState {
-    size: usize,
-    running: HashMap<ThreadId, JoinHandle<()>>,
-    queue: VecDeque<Task>,
+    queue: BinaryHeap<QueuedTask>,
+    sequence: u64,
+    running: usize,
+    panicked: usize,
+    closed: bool,
 }
 
 impl TaskPoolState {
-    pub fn new(size: usize) -> Self {
+    pub fn new() -> Self {
         TaskPoolState {
-            size,
-            running: HashMap::new(),
-            queue: VecDeque::new(),
+            queue: BinaryHeap::new(),
+            sequence: 0,
+            running: 0,
+            panicked: 0,
+            closed: false,
         }
     }
 }
 
+impl Default for TaskPoolState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct TaskPoolShared {
+    state: Mutex<TaskPoolState>,
+    available: Condvar,
+    idle: Condvar,
+}
+
 pub struct TaskPool {
-    state: Arc<Mutex<TaskPoolState>>,
+    shared: Arc<TaskPoolShared>,
+    workers: Vec<JoinHandle<()>>,
 }
 
 impl TaskPool {
+    pub const NORMAL_PRIORITY: u8 = 128;
+
     pub fn new(size: usize) -> Self {
-        TaskPool {
-            state: Arc::new(Mutex::new(TaskPoolState::new(size))),
+        let shared = Arc::new(TaskPoolShared {
+            state: Mutex::new(TaskPoolState::new()),
+            available: Condvar::new(),
+            idle: Condvar::new(),
+        });
+
+        let workers = (0..size.max(1))
+            .map(|_| {
+                let shared = Arc::clone(&shared);
+                std::thread::spawn(move || TaskPool::work(shared))
+            })
+            .collect();
+
+        TaskPool { shared, workers }
+    }
+
+    pub fn size(&self) -> usize {
+        self.workers.len()
+    }
+
+    pub fn panicked(&self) -> usize {
+        self.shared.state.lock().unwrap().panicked
+    }
+
+    pub fn queued_len(&self) -> usize {
+        self.shared.state.lock().unwrap().queue.len()
+    }
+
+    pub fn running_len(&self) -> usize {
+        self.shared.state.lock().unwrap().running
+    }
+
+    pub fn is_shutdown(&self) -> bool {
+        self.shared.state.lock().unwrap().closed
+    }
+
+    /// Stops accepting new tasks and blocks until every queued task has finished.
+    pub fn shutdown(&self) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.closed = true;
+        self.shared.available.notify_all();
+
+        while !state.queue.is_empty() || state.running > 0 {
+            state = self.shared.idle.wait(state).unwrap();
         }
     }
 
     pub fn spawn(&self, task: impl FnOnce() + Send + 'static) {
-        let mut state = self.state.lock().unwrap();
-        state.queue.push_back(Box::new(task));
-        drop(state);
-        TaskPool::run_one(Arc::clone(&self.state));
+        self.spawn_with_priority(Self::NORMAL_PRIORITY, task);
     }
 
-    fn run_one(state: Arc<Mutex<TaskPoolState>>) {
-        let mut locked = state.lock().unwrap();
-        if locked.running.len() >= locked.size {
+    /// Queues a task ahead of any queued task with a lower priority.
+    pub fn spawn_with_priority(&self, priority: u8, task: impl FnOnce() + Send + 'static) {
+        let mut state = self.shared.state.lock().unwrap();
+        if state.closed {
             return;
         }
 
-        if let Some(task) = locked.queue.pop_front() {
-            let inner = Arc::clone(&state);
-            let handle = std::thread::spawn(move || {
-                task();
-                let mut state = inner.lock().unwrap();
-                state.running.remove(&std::thread::current().id());
-                drop(state);
-                TaskPool::run_one(inner);
-            });
+        let sequence = state.sequence;
+        state.sequence += 1;
+        state.queue.push(QueuedTask {
+            priority,
+            sequence,
+            task: Box::new(task),
+        });
+        drop(state);
+        self.shared.available.notify_one();
+    }
+
+    pub fn spawn_with_result<T: Send + 'static>(
+        &self,
+        task: impl FnOnce() -> T + Send + 'static,
+    ) -> TaskHandle<T> {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        self.spawn(move || {
+            let _ = sender.send(task());
+        });
+
+        TaskHandle::new(receiver)
+    }
+
+    fn work(shared: Arc<TaskPoolShared>) {
+        loop {
+            let task = {
+                let mut state = shared.state.lock().unwrap();
+                loop {
+                    if let Some(queued) = state.queue.pop() {
+                        state.running += 1;
+                        break queued.task;
+                    } else if state.closed {
+                        return;
+                    }
+
+                    state = shared.available.wait(state).unwrap();
+                }
+            };
 
-            locked.running.insert(handle.thread().id(), handle);
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(task));
+
+            let mut state = shared.state.lock().unwrap();
+            state.running -= 1;
+            if result.is_err() {
+                state.panicked += 1;
+            }
+
+            if state.running == 0 && state.queue.is_empty() {
+                shared.idle.notify_all();
+            }
         }
     }
 }
 
 impl Drop for TaskPool {
     fn drop(&mut self) {
-        let mut running = match self.state.lock() {
-            Ok(mut state) => std::mem::take(&mut state.running),
+        match self.shared.state.lock() {
+            Ok(mut state) => state.closed = true,
             Err(_) => return,
-        };
-        while !running.is_empty() {
-            for (_, handle) in running {
-                handle.join().unwrap();
-            }
+        }
 
-            let mut state = self.state.lock().unwrap();
-            running = std::mem::take(&mut state.running);
+        self.shared.available.notify_all();
+        for handle in self.workers.drain(..) {
+            handle.join().unwrap();
         }
     }
 }
 
-pub type ScopedTask<'a> = Box<dyn FnOnce() + Send + 'a>;
+pub struct TaskHandle<T> {
+    receiver: Receiver<T>,
+}
+
+impl<T> TaskHandle<T> {
+    fn new(receiver: Receiver<T>) -> Self {
+        TaskHandle { receiver }
+    }
 
-pub struct ScopedTaskPool<'a> {
+    pub fn join(self) -> Option<T> {
+        self.receiver.recv().ok()
+    }
+
+    pub fn try_join(&self) -> Option<T> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+pub type ScopedTask<'a, T = ()> = Box<dyn FnOnce() -> T + Send + 'a>;
+
+pub struct ScopedTaskPool<'a, T = ()> {
     size: usize,
-    queue: VecDeque<ScopedTask<'a>>,
+    queue: VecDeque<ScopedTask<'a, T>>,
 }
 
-impl<'a> ScopedTaskPool<'a> {
+impl<'a, T: Send + 'a> ScopedTaskPool<'a, T> {
     pub fn new(size: usize) -> Self {
         ScopedTaskPool {
-            size,
+            size: size.max(1),
             queue: VecDeque::new(),
         }
     }
 
-    pub fn spawn(&mut self, task: impl FnOnce() + Send + 'a) -> &mut Self {
+    pub fn spawn(&mut self, task: impl FnOnce() -> T + Send + 'a) -> &mut Self {
         self.queue.push_back(Box::new(task));
         self
     }
 
     pub fn run(&mut self) {
+        self.run_map();
+    }
+
+    /// Runs every queued task and returns their results in spawn order.
+    pub fn run_map(&mut self) -> Vec<T> {
+        let mut results = Vec::with_capacity(self.queue.len());
         while !self.queue.is_empty() {
             let len = self.queue.len().min(self.size);
             let tasks = self.queue.drain(..len).collect::<Vec<_>>();
-            std::thread::scope(move |scope| {
-                for task in tasks {
-                    scope.spawn(|| task());
+            std::thread::scope(|scope| {
+                let handles = tasks
+                    .into_iter()
+                    .map(|task| scope.spawn(task))
+                    .collect::<Vec<_>>();
+
+                for handle in handles {
+                    match handle.join() {
+                        Ok(result) => results.push(result),
+                        Err(panic) => std::panic::resume_unwind(panic),
+                    }
                 }
             });
         }
+
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+    #[test]
+    fn workers_are_reused_across_tasks() {
+        let pool = TaskPool::new(4);
+        let count = Arc::new(AtomicUsize::new(0));
+        let threads = Arc::new(Mutex::new(std::collections::HashSet::new()));
+
+        for _ in 0..1000 {
+            let count = Arc::clone(&count);
+            let threads = Arc::clone(&threads);
+            pool.spawn(move || {
+                count.fetch_add(1, AtomicOrdering::SeqCst);
+                threads.lock().unwrap().insert(std::thread::current().id());
+            });
+        }
+
+        drop(pool);
+        assert_eq!(count.load(AtomicOrdering::SeqCst), 1000);
+        assert!(threads.lock().unwrap().len() <= 4);
+    }
+
+    #[test]
+    fn spawn_with_result_returns_values() {
+        let pool = TaskPool::new(2);
+        let handles = (0..10u32)
+            .map(|value| pool.spawn_with_result(move || value * value))
+            .collect::<Vec<_>>();
+
+        let results = handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            results,
+            (0..10u32).map(|value| value * value).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn panicking_task_does_not_stop_the_pool() {
+        let pool = TaskPool::new(1);
+        pool.spawn(|| panic!("task failed"));
+        let handle = pool.spawn_with_result(|| 42);
+
+        assert_eq!(handle.join(), Some(42));
+        pool.shutdown();
+        assert_eq!(pool.panicked(), 1);
+    }
+
+    #[test]
+    fn shutdown_drains_queue_and_rejects_new_tasks() {
+        let pool = TaskPool::new(2);
+        let count = Arc::new(AtomicUsize::new(0));
+        for _ in 0..50 {
+            let count = Arc::clone(&count);
+            pool.spawn(move || {
+                std::thread::sleep(std::time::Duration::from_millis(1));
+                count.fetch_add(1, AtomicOrdering::SeqCst);
+            });
+        }
+
+        pool.shutdown();
+        assert!(pool.is_shutdown());
+        assert_eq!(count.load(AtomicOrdering::SeqCst), 50);
+
+        let late = Arc::clone(&count);
+        pool.spawn(move || {
+            late.fetch_add(1, AtomicOrdering::SeqCst);
+        });
+        drop(pool);
+        assert_eq!(count.load(AtomicOrdering::SeqCst), 50);
+    }
+
+    #[test]
+    fn scoped_results_keep_spawn_order() {
+        let inputs = (0..10u64).collect::<Vec<_>>();
+        let mut pool = ScopedTaskPool::new(3);
+        for value in &inputs {
+            pool.spawn(move || {
+                std::thread::sleep(std::time::Duration::from_millis(10 - value));
+                value * 2
+            });
+        }
+
+        assert_eq!(
+            pool.run_map(),
+            inputs.iter().map(|value| value * 2).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn reports_queued_and_running_counts() {
+        let pool = TaskPool::new(2);
+        let (release, gate) = std::sync::mpsc::channel::<()>();
+        let gate = Arc::new(Mutex::new(gate));
+        for _ in 0..5 {
+            let gate = Arc::clone(&gate);
+            pool.spawn(move || {
+                let _ = gate.lock().unwrap().recv();
+            });
+        }
+
+        while pool.running_len() < 2 {
+            std::thread::yield_now();
+        }
+        assert_eq!(pool.running_len(), 2);
+        assert_eq!(pool.queued_len(), 3);
+
+        drop(release);
+        pool.shutdown();
+        assert_eq!(pool.queued_len(), 0);
+        assert_eq!(pool.running_len(), 0);
+    }
+
+    #[test]
+    fn higher_priority_tasks_run_first() {
+        let pool = TaskPool::new(1);
+        let (release, gate) = std::sync::mpsc::channel::<()>();
+        pool.spawn(move || {
+            let _ = gate.recv();
+        });
+        while pool.running_len() < 1 {
+            std::thread::yield_now();
+        }
+
+        let order = Arc::new(Mutex::new(Vec::new()));
+        for (priority, name) in [
+            (1, "low"),
+            (200, "high"),
+            (TaskPool::NORMAL_PRIORITY, "normal"),
+            (200, "high2"),
+        ] {
+            let order = Arc::clone(&order);
+            pool.spawn_with_priority(priority, move || order.lock().unwrap().push(name));
+        }
+
+        drop(release);
+        pool.shutdown();
+        assert_eq!(*order.lock().unwrap(), ["high", "high2", "normal", "low"]);
     }
 }
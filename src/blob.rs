@@ -1,4 +1,4 @@
-use std::{alloc::Layout, fmt::Debug, marker::PhantomData};
+use std::{alloc::Layout, any::TypeId, fmt::Debug, marker::PhantomData};
 
 pub struct Blob {
     data: Vec<u8>,
@@ -7,6 +7,8 @@ pub struct Blob {
     layout: Layout,
     aligned_layout: Layout,
     drop: Option<fn(data: *mut u8)>,
+    clone: Option<fn(src: *const u8, dst: *mut u8)>,
+    ty: Option<TypeId>,
 }
 
 impl Blob {
@@ -22,20 +24,33 @@ impl Blob {
 
         Self {
             data,
-            capacity,
+            capacity: Self::element_capacity(aligned_layout, capacity),
             length: 0,
             layout,
             aligned_layout,
             drop,
+            clone: None,
+            ty: Some(TypeId::of::<T>()),
         }
     }
 
+    /// Creates a blob whose values can be duplicated with [`Blob::try_clone`].
+    pub fn new_clone<T: Clone + 'static>(capacity: usize) -> Self {
+        let mut blob = Blob::new::<T>(capacity);
+        blob.clone = Some(clone::<T>);
+        blob
+    }
+
     pub fn from<T: 'static>(value: T) -> Self {
         let layout = Layout::new::<T>();
         let aligned_layout = layout.pad_to_align();
         let mut data = Vec::with_capacity(aligned_layout.size() * 2);
+        let base = match layout.size() {
+            0 => layout.align() as *mut u8,
+            _ => data.as_mut_ptr(),
+        };
         unsafe {
-            std::ptr::write(data.as_mut_ptr() as *mut T, value);
+            std::ptr::write(base as *mut T, value);
             data.set_len(aligned_layout.size());
         }
 
@@ -46,11 +61,13 @@ impl Blob {
 
         Self {
             data,
-            capacity: 2,
+            capacity: Self::element_capacity(aligned_layout, 2),
             length: 1,
             layout,
             aligned_layout,
             drop,
+            clone: None,
+            ty: Some(TypeId::of::<T>()),
         }
     }
 
@@ -60,12 +77,51 @@ impl Blob {
 
         Self {
             data,
-            capacity,
+            capacity: Self::element_capacity(aligned_layout, capacity),
             length: 0,
             layout,
             aligned_layout,
             drop,
+            clone: None,
+            ty: None,
+        }
+    }
+
+    /// Creates an empty blob that stores the same element type as this one.
+    pub fn with_same_layout(&self, capacity: usize) -> Self {
+        let mut blob = Blob::with_layout(self.layout, capacity, self.drop);
+        blob.clone = self.clone;
+        blob.ty = self.ty;
+        blob
+    }
+
+    /// Clones every value into a new blob, if the element type registered a clone function.
+    pub fn try_clone(&self) -> Option<Blob> {
+        let clone = self.clone?;
+        let mut blob = self.with_same_layout(self.length);
+        for index in 0..self.length {
+            clone(self.offset(index), blob.offset(index));
+            blob.length += 1;
+            unsafe { blob.data.set_len(blob.length * blob.aligned_layout.size()) };
         }
+
+        Some(blob)
+    }
+
+    /// Clones the value at `index` into a single-element blob, if the element type
+    /// registered a clone function.
+    pub fn try_clone_at(&self, index: usize) -> Option<Blob> {
+        let clone = self.clone?;
+        if index >= self.length {
+            return None;
+        }
+
+        let mut blob = self.with_same_layout(1);
+        clone(self.offset(index), blob.offset(0));
+        blob.length = 1;
+        unsafe { blob.data.set_len(blob.aligned_layout.size()) };
+
+        Some(blob)
     }
 
     pub fn layout(&self) -> &Layout {
@@ -88,16 +144,29 @@ impl Blob {
         self.drop.as_ref()
     }
 
+    pub fn ty(&self) -> Option<TypeId> {
+        self.ty
+    }
+
+    /// Checks whether `T` is the element type of this blob. Blobs created from a raw
+    /// layout only know their layout, so for those the layouts are compared instead.
+    pub fn is<T: 'static>(&self) -> bool {
+        match self.ty {
+            Some(ty) => ty == TypeId::of::<T>(),
+            None => Layout::new::<T>() == self.layout,
+        }
+    }
+
     pub fn get<T: 'static>(&self, index: usize) -> Option<&T> {
-        if index < self.length {
+        if index < self.length && self.is::<T>() {
             Some(unsafe { &*(self.offset(index) as *const T) })
         } else {
             None
         }
     }
 
-    pub fn get_mut<T>(&self, index: usize) -> Option<&mut T> {
-        if index < self.length {
+    pub fn get_mut<T: 'static>(&mut self, index: usize) -> Option<&mut T> {
+        if index < self.length && self.is::<T>() {
             Some(unsafe { &mut *(self.offset(index) as *mut T) })
         } else {
             None
@@ -105,6 +174,8 @@ impl Blob {
     }
 
     pub fn push<T: 'static>(&mut self, value: T) {
+        self.check_type::<T>();
+
         if self.length == self.capacity {
             self.reserve(self.capacity.max(1));
         }
@@ -119,6 +190,8 @@ impl Blob {
     }
 
     pub fn insert<T: 'static>(&mut self, index: usize, value: T) {
+        self.check_type::<T>();
+
         if index >= self.length {
             panic!("Index out of bounds.")
         }
@@ -131,7 +204,8 @@ impl Blob {
             let src = self.offset(index);
             let dst = self.offset(index + 1);
 
-            std::ptr::copy(src, dst, self.length - index);
+            let count = (self.length - index) * self.aligned_layout.size();
+            std::ptr::copy(src, dst, count);
             std::ptr::write(src as *mut T, value);
 
             self.length += 1;
@@ -140,6 +214,8 @@ impl Blob {
     }
 
     pub fn remove<T: 'static>(&mut self, index: usize) -> T {
+        self.check_type::<T>();
+
         if index >= self.length {
             panic!("Index out of bounds.")
         }
@@ -151,7 +227,7 @@ impl Blob {
             if index + 1 < self.length {
                 let dst = src as *mut u8;
                 let src = self.offset(index + 1);
-                let count = self.length - (index + 1) * self.aligned_layout.size();
+                let count = (self.length - (index + 1)) * self.aligned_layout.size();
                 std::ptr::copy(src, dst, count);
             }
 
@@ -167,6 +243,8 @@ impl Blob {
     }
 
     pub fn swap_remove<T: 'static>(&mut self, index: usize) -> T {
+        self.check_type::<T>();
+
         if index >= self.length {
             panic!("Index out of bounds.")
         }
@@ -199,7 +277,10 @@ impl Blob {
     }
 
     pub fn extend(&mut self, mut blob: Blob) {
-        if blob.aligned_layout != self.aligned_layout || blob.layout != self.layout {
+        if blob.aligned_layout != self.aligned_layout
+            || blob.layout != self.layout
+            || blob.ty.zip(self.ty).is_some_and(|(a, b)| a != b)
+        {
             panic!("Layouts are different")
         }
 
@@ -216,7 +297,10 @@ impl Blob {
     }
 
     pub fn push_blob(&mut self, mut blob: Blob) {
-        if blob.aligned_layout != self.aligned_layout || blob.layout != self.layout {
+        if blob.aligned_layout != self.aligned_layout
+            || blob.layout != self.layout
+            || blob.ty.zip(self.ty).is_some_and(|(a, b)| a != b)
+        {
             panic!("Layouts are different")
         }
 
@@ -228,8 +312,11 @@ impl Blob {
         blob.capacity = 0;
     }
 
-    pub fn insert_blob(&mut self, index: usize, blob: Blob) {
-        if blob.aligned_layout != self.aligned_layout || blob.layout != self.layout {
+    pub fn insert_blob(&mut self, index: usize, mut blob: Blob) {
+        if blob.aligned_layout != self.aligned_layout
+            || blob.layout != self.layout
+            || blob.ty.zip(self.ty).is_some_and(|(a, b)| a != b)
+        {
             panic!("Layouts are different")
         }
 
@@ -237,14 +324,16 @@ impl Blob {
             panic!("Index out of bounds.")
         }
 
-        self.reserve(blob.capacity);
+        if blob.length == 0 {
+            return;
+        }
+
+        self.reserve(blob.length);
         unsafe {
-            if self.length > 1 {
-                let src = self.offset(index);
-                let dst = self.offset(index + blob.length);
-                let count = (self.capacity - index) * self.aligned_layout.size();
-                std::ptr::copy(src, dst, count);
-            }
+            let src = self.offset(index);
+            let dst = self.offset(index + blob.length);
+            let count = (self.length - index) * self.aligned_layout.size();
+            std::ptr::copy(src, dst, count);
 
             let count = blob.length * self.aligned_layout.size();
             std::ptr::copy(blob.offset(0), self.offset(index), count);
@@ -252,6 +341,9 @@ impl Blob {
             self.length += blob.length;
             self.data.set_len(self.length * self.aligned_layout.size());
         }
+
+        // The values now live in this blob, so the source must not drop them.
+        blob.length = 0;
     }
 
     pub fn remove_blob(&mut self, index: usize) -> Blob {
@@ -276,7 +368,9 @@ impl Blob {
             aligned_layout: self.aligned_layout,
             layout: self.layout,
             drop: self.drop.clone(),
-            capacity: 1,
+            clone: self.clone,
+            ty: self.ty,
+            capacity: Self::element_capacity(self.aligned_layout, 1),
             length: 1,
             data,
         }
@@ -289,12 +383,15 @@ impl Blob {
 
         let start = (self.length - 1) * self.aligned_layout.size();
         let end = start + self.aligned_layout.size();
-        let data = self.data.drain(start..end).collect::<Vec<_>>();
+        let last = self.data.drain(start..end).collect::<Vec<_>>();
 
-        let start = index * self.aligned_layout.size();
-        let end = start + self.aligned_layout().size();
-
-        let data = self.data.splice(start..end, data).collect::<Vec<_>>();
+        let data = if index == self.length - 1 {
+            last
+        } else {
+            let start = index * self.aligned_layout.size();
+            let end = start + self.aligned_layout().size();
+            self.data.splice(start..end, last).collect::<Vec<_>>()
+        };
 
         self.length -= 1;
         unsafe {
@@ -309,12 +406,15 @@ impl Blob {
             aligned_layout: self.aligned_layout,
             layout: self.layout,
             drop: self.drop.clone(),
-            capacity: 1,
+            clone: self.clone,
+            ty: self.ty,
+            capacity: Self::element_capacity(self.aligned_layout, 1),
             length: 1,
             data,
         }
     }
 
+    /// Drops every value but keeps the allocation for reuse.
     pub fn clear(&mut self) {
         if let Some(drop) = self.drop {
             for index in 0..self.length {
@@ -324,18 +424,20 @@ impl Blob {
 
         self.data.clear();
         self.length = 0;
-        self.capacity = 0;
+        self.capacity = self.allocated_capacity();
     }
 
-    pub fn iter<T: 'static>(&self) -> BlobIter<T> {
+    pub fn iter<T: 'static>(&self) -> BlobIter<'_, T> {
         BlobIter::<T>::new(self)
     }
 
-    pub fn iter_mut<T: 'static>(&mut self) -> BlobIterMut<T> {
+    pub fn iter_mut<T: 'static>(&mut self) -> BlobIterMut<'_, T> {
         BlobIterMut::<T>::new(self)
     }
 
-    pub fn ptr<T: 'static>(&self, index: usize) -> Ptr<T> {
+    pub fn ptr<T: 'static>(&mut self, index: usize) -> Ptr<'_, T> {
+        self.check_type::<T>();
+
         if index >= self.length {
             panic!("Index out of bounds.")
         }
@@ -343,18 +445,24 @@ impl Blob {
         Ptr::new(self.offset(index) as *mut T)
     }
 
+    /// Reserves room for at least `additional` more elements beyond the current length.
     pub fn reserve(&mut self, additional: usize) {
         self.data
             .reserve_exact(additional * self.aligned_layout.size());
 
-        self.capacity = self.data.capacity() / self.aligned_layout.size().clamp(1, usize::MAX);
+        self.capacity = self.allocated_capacity();
     }
 
     pub fn shrink(&mut self, min_capacity: usize) {
         self.data
             .shrink_to(min_capacity * self.aligned_layout.size());
 
-        self.capacity = self.data.capacity() / self.aligned_layout.size().clamp(1, usize::MAX);
+        self.capacity = self.allocated_capacity();
+    }
+
+    /// Releases any capacity beyond the current length.
+    pub fn shrink_to_fit(&mut self) {
+        self.shrink(self.length)
     }
 
     pub fn is_empty(&self) -> bool {
@@ -367,7 +475,38 @@ impl Blob {
 }
 
 impl Blob {
+    /// Zero-sized elements never need storage, so their capacity is unbounded and
+    /// only the length is tracked.
+    fn element_capacity(aligned_layout: Layout, capacity: usize) -> usize {
+        match aligned_layout.size() {
+            0 => usize::MAX,
+            _ => capacity,
+        }
+    }
+
+    fn allocated_capacity(&self) -> usize {
+        match self.aligned_layout.size() {
+            0 => usize::MAX,
+            size => self.data.capacity() / size,
+        }
+    }
+
+    fn check_type<T: 'static>(&self) {
+        if !self.is::<T>() {
+            panic!(
+                "Blob does not store values of type {}",
+                std::any::type_name::<T>()
+            )
+        }
+    }
+
     fn offset(&self, offset: usize) -> *mut u8 {
+        // `Vec<u8>`'s dangling pointer is only 1-aligned, so zero-sized values use a
+        // dangling pointer with their own alignment, as `BlobCell` does.
+        if self.aligned_layout.size() == 0 {
+            return self.layout.align() as *mut u8;
+        }
+
         let count: isize = (offset * self.aligned_layout.size()).try_into().unwrap();
         let bounds: isize = (self.capacity * self.aligned_layout.size()
             - self.aligned_layout.size())
@@ -386,21 +525,30 @@ impl Drop for Blob {
     }
 }
 
+/// A single type-erased value. Only `Send + Sync` values can be stored, which is
+/// what makes the cell itself safe to share across threads.
 pub struct BlobCell {
-    data: Vec<u8>,
+    data: *mut u8,
     layout: Layout,
     drop: Option<fn(data: *mut u8)>,
 }
 
 impl BlobCell {
-    pub fn new<T: 'static>(value: T) -> Self {
+    pub fn new<T: Send + Sync + 'static>(value: T) -> Self {
         let layout = Layout::new::<T>();
-        let data = unsafe {
-            let ptr = std::ptr::addr_of!(value) as *mut u8;
-            std::mem::forget(value);
-            Vec::from_raw_parts(ptr, layout.size(), layout.size())
+        let data = match layout.size() {
+            0 => layout.align() as *mut u8,
+            _ => unsafe {
+                let data = std::alloc::alloc(layout);
+                if data.is_null() {
+                    std::alloc::handle_alloc_error(layout);
+                }
+                data
+            },
         };
 
+        unsafe { std::ptr::write(data as *mut T, value) };
+
         let drop = match std::mem::needs_drop::<T>() {
             true => Some(drop::<T> as fn(*mut u8)),
             false => None,
@@ -418,38 +566,50 @@ impl BlobCell {
     }
 
     pub fn value<T: 'static>(&self) -> &T {
-        unsafe { &*(self.data.as_ptr() as *const T) }
+        unsafe { &*(self.data as *const T) }
     }
 
     pub fn value_mut<T: 'static>(&mut self) -> &mut T {
-        unsafe { &mut *(self.data.as_mut_ptr() as *mut T) }
+        unsafe { &mut *(self.data as *mut T) }
     }
 
-    pub fn ptr<T: 'static>(&self) -> Ptr<T> {
-        Ptr::new(self.data.as_ptr() as *mut T)
+    pub fn ptr<T: 'static>(&mut self) -> Ptr<'_, T> {
+        Ptr::new(self.data as *mut T)
     }
 
     pub fn take<T: 'static>(self) -> T {
         unsafe {
-            let value = (self.data.as_ptr() as *const T).read();
+            let value = (self.data as *const T).read();
+            self.dealloc();
             std::mem::forget(self);
             value
         }
     }
 }
 
+impl BlobCell {
+    fn dealloc(&self) {
+        if self.layout.size() > 0 {
+            unsafe { std::alloc::dealloc(self.data, self.layout) }
+        }
+    }
+}
+
 impl Drop for BlobCell {
     fn drop(&mut self) {
         if let Some(drop) = self.drop {
-            drop(self.data.as_mut_ptr());
+            drop(self.data);
         }
 
-        let mut data = std::mem::take(&mut self.data);
-        data.clear();
-        std::mem::forget(data);
+        self.dealloc();
     }
 }
 
+// SAFETY: `BlobCell::new` only accepts `Send + Sync` values, and the cell owns
+// its value exclusively.
+unsafe impl Send for BlobCell {}
+unsafe impl Sync for BlobCell {}
+
 impl<T: 'static> From<Vec<T>> for Blob {
     fn from(value: Vec<T>) -> Self {
         let mut blob = Blob::new::<T>(value.capacity());
@@ -458,6 +618,13 @@ impl<T: 'static> From<Vec<T>> for Blob {
     }
 }
 
+fn clone<T: Clone>(src: *const u8, dst: *mut u8) {
+    unsafe {
+        let value = (*(src as *const T)).clone();
+        std::ptr::write(dst as *mut T, value);
+    }
+}
+
 fn drop<T>(data: *mut u8) {
     unsafe {
         let raw = data as *mut T;
@@ -558,3 +725,50 @@ impl<'a, T: 'static> Iterator for BlobIterMut<'a, T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reserve_and_shrink_adjust_capacity() {
+        let mut blob = Blob::new::<u64>(0);
+        blob.reserve(64);
+        let capacity = blob.capacity();
+        assert!(capacity >= 64);
+
+        let data = blob.bytes().as_ptr();
+        for value in 0..capacity as u64 {
+            blob.push(value);
+        }
+        assert_eq!(blob.bytes().as_ptr(), data);
+        assert_eq!(blob.capacity(), capacity);
+
+        blob.clear();
+        blob.push(7u64);
+        blob.shrink_to_fit();
+        assert!(blob.capacity() < capacity);
+        assert!(blob.capacity() >= 1);
+        assert_eq!(blob.get::<u64>(0), Some(&7));
+    }
+
+    #[test]
+    fn zero_sized_values_stay_aligned() {
+        #[derive(Debug, PartialEq)]
+        #[repr(align(8))]
+        struct Tag;
+
+        let mut blob = Blob::new::<Tag>(0);
+        for _ in 0..3 {
+            blob.push(Tag);
+        }
+
+        assert_eq!(blob.len(), 3);
+        let value = blob.get::<Tag>(1).unwrap();
+        assert_eq!(value as *const Tag as usize % 8, 0);
+        assert_eq!(blob.remove::<Tag>(0), Tag);
+
+        let blob = Blob::from(Tag);
+        assert_eq!(blob.get::<Tag>(0), Some(&Tag));
+    }
+}
@@ -1,5 +1,7 @@
 use std::{collections::HashMap, hash::Hash};
 
+/// A map stored as parallel key and value vectors. Iteration follows insertion
+/// order; overwriting an existing key keeps its position.
 pub struct DenseMap<K: Clone + Hash + Eq, V> {
     values: Vec<V>,
     keys: Vec<K>,
@@ -16,12 +18,32 @@ impl<K: Clone + Hash + Eq, V> DenseMap<K, V> {
     }
 
     pub fn insert(&mut self, key: K, value: V) {
+        if let Some(index) = self.map.get(&key) {
+            self.values[*index] = value;
+            return;
+        }
+
         let index = self.values.len();
         self.values.push(value);
         self.keys.push(key.clone());
         self.map.insert(key, index);
     }
 
+    pub fn get_or_insert_with(&mut self, key: K, f: impl FnOnce() -> V) -> &mut V {
+        let index = match self.map.get(&key) {
+            Some(index) => *index,
+            None => {
+                let index = self.values.len();
+                self.values.push(f());
+                self.keys.push(key.clone());
+                self.map.insert(key, index);
+                index
+            }
+        };
+
+        &mut self.values[index]
+    }
+
     pub fn get(&self, key: &K) -> Option<&V> {
         self.map.get(key).map(|&index| &self.values[index])
     }
@@ -31,20 +53,28 @@ impl<K: Clone + Hash + Eq, V> DenseMap<K, V> {
         Some(&mut self.values[*index])
     }
 
+    /// Removes an entry, shifting later entries down so the remaining order is
+    /// unchanged.
     pub fn remove(&mut self, key: &K) -> Option<V> {
         let index = self.map.remove(key)?;
         let value = self.values.remove(index);
         self.keys.remove(index);
-        self.map.insert(self.keys[index].clone(), index);
+        for (index, key) in self.keys.iter().enumerate().skip(index) {
+            self.map.insert(key.clone(), index);
+        }
 
         Some(value)
     }
 
+    /// Removes an entry in constant time by moving the last entry into its slot,
+    /// which changes the iteration order.
     pub fn swap_remove(&mut self, key: &K) -> Option<V> {
         let index = self.map.remove(key)?;
         let value = self.values.swap_remove(index);
         self.keys.swap_remove(index);
-        self.map.insert(self.keys[index].clone(), index);
+        if let Some(key) = self.keys.get(index) {
+            self.map.insert(key.clone(), index);
+        }
 
         Some(value)
     }
@@ -59,6 +89,7 @@ impl<K: Clone + Hash + Eq, V> DenseMap<K, V> {
                 self.values.remove(i);
                 self.keys.remove(i);
             } else {
+                self.map.insert(key.clone(), i);
                 i += 1;
             }
         }
@@ -83,8 +114,46 @@ impl<K: Clone + Hash + Eq, V> DenseMap<K, V> {
     pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
         self.values.iter_mut()
     }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.keys.clear();
+        self.values.clear();
+    }
+}
+
+impl<K: Clone + Hash + Eq, V> FromIterator<(K, V)> for DenseMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = DenseMap::new();
+        for (key, value) in iter {
+            map.insert(key, value);
+        }
+        map
+    }
+}
+
+impl<K: Clone + Hash + Eq, V> IntoIterator for DenseMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = std::iter::Zip<std::vec::IntoIter<K>, std::vec::IntoIter<V>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.keys.into_iter().zip(self.values)
+    }
 }
 
+/// A set stored as a key vector with the same ordering rules as [`DenseMap`].
 pub struct DenseSet<K: Clone + Hash + Eq> {
     keys: Vec<K>,
     map: HashMap<K, usize>,
@@ -102,6 +171,11 @@ impl<K: Clone + Hash + Eq> DenseSet<K> {
         self.map.get(key).copied()
     }
 
+    /// The key stored at `index`, the inverse of [`DenseSet::index`].
+    pub fn key_at(&self, index: usize) -> Option<&K> {
+        self.keys.get(index)
+    }
+
     pub fn insert(&mut self, key: K) -> usize {
         let index = self.keys.len();
         self.keys.push(key.clone());
@@ -116,7 +190,9 @@ impl<K: Clone + Hash + Eq> DenseSet<K> {
     pub fn remove(&mut self, key: &K) -> Option<K> {
         let index = self.map.remove(key)?;
         let key = self.keys.remove(index);
-        self.map.insert(self.keys[index].clone(), index);
+        for (index, key) in self.keys.iter().enumerate().skip(index) {
+            self.map.insert(key.clone(), index);
+        }
 
         Some(key)
     }
@@ -124,7 +200,9 @@ impl<K: Clone + Hash + Eq> DenseSet<K> {
     pub fn swap_remove(&mut self, key: &K) -> Option<K> {
         let index = self.map.remove(key)?;
         let key = self.keys.swap_remove(index);
-        self.map.insert(self.keys[index].clone(), index);
+        if let Some(key) = self.keys.get(index) {
+            self.map.insert(key.clone(), index);
+        }
 
         Some(key)
     }
@@ -137,6 +215,7 @@ impl<K: Clone + Hash + Eq> DenseSet<K> {
                 self.map.remove(key);
                 self.keys.remove(i);
             } else {
+                self.map.insert(key.clone(), i);
                 i += 1;
             }
         }
@@ -155,6 +234,12 @@ impl<K: Clone + Hash + Eq> DenseSet<K> {
         self.keys.iter_mut()
     }
 
+    /// Iterates keys with their dense indices, which line up with the rows of
+    /// any column kept in step with this set.
+    pub fn iter_indexed(&self) -> impl Iterator<Item = (usize, &K)> {
+        self.keys.iter().enumerate()
+    }
+
     pub fn len(&self) -> usize {
         self.keys.len()
     }
@@ -168,3 +253,107 @@ impl<K: Clone + Hash + Eq> DenseSet<K> {
         self.keys.clear();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remove_keeps_remaining_keys_resolving() {
+        let mut map = DenseMap::new();
+        map.insert("a", 1);
+        map.insert("b", 2);
+        map.insert("c", 3);
+
+        assert_eq!(map.remove(&"b"), Some(2));
+        assert_eq!(map.get(&"a"), Some(&1));
+        assert_eq!(map.get(&"c"), Some(&3));
+        assert_eq!(map.get(&"b"), None);
+    }
+
+    #[test]
+    fn set_removes_last_and_only_keys() {
+        let mut set = DenseSet::new();
+        set.insert(1);
+        set.insert(2);
+        assert_eq!(set.swap_remove(&2), Some(2));
+        assert_eq!(set.remove(&1), Some(1));
+        assert!(set.is_empty());
+
+        set.insert(3);
+        assert_eq!(set.swap_remove(&3), Some(3));
+        assert_eq!(set.swap_remove(&3), None);
+        assert_eq!(set.remove(&3), None);
+    }
+
+    #[test]
+    fn map_len_contains_and_clear() {
+        let mut map = DenseMap::new();
+        assert!(map.is_empty());
+        map.insert(1, "one");
+        map.insert(2, "two");
+        map.insert(1, "uno");
+
+        assert_eq!(map.len(), 2);
+        assert!(!map.is_empty());
+        assert!(map.contains_key(&1));
+        assert!(!map.contains_key(&3));
+
+        map.clear();
+        assert!(map.is_empty());
+        assert!(!map.contains_key(&1));
+    }
+
+    #[test]
+    fn get_or_insert_with_creates_one_slot() {
+        let mut map = DenseMap::new();
+        *map.get_or_insert_with("a", || 1) += 1;
+        *map.get_or_insert_with("a", || panic!("slot already exists")) += 1;
+
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.get(&"a"), Some(&3));
+    }
+
+    #[test]
+    fn map_collects_and_round_trips() {
+        let map = [("a", 1), ("b", 2), ("c", 3)]
+            .into_iter()
+            .collect::<DenseMap<_, _>>();
+        assert_eq!(map.get(&"b"), Some(&2));
+
+        let entries = map.into_iter().collect::<Vec<_>>();
+        assert_eq!(entries, [("a", 1), ("b", 2), ("c", 3)]);
+    }
+
+    #[test]
+    fn remove_preserves_order_and_swap_remove_does_not() {
+        let mut map = [("a", 1), ("b", 2), ("c", 3), ("d", 4)]
+            .into_iter()
+            .collect::<DenseMap<_, _>>();
+
+        assert_eq!(map.remove(&"b"), Some(2));
+        assert_eq!(map.keys().copied().collect::<Vec<_>>(), ["a", "c", "d"]);
+        assert_eq!(map.values().copied().collect::<Vec<_>>(), [1, 3, 4]);
+        assert_eq!(map.get(&"d"), Some(&4));
+
+        assert_eq!(map.swap_remove(&"a"), Some(1));
+        assert_eq!(map.keys().copied().collect::<Vec<_>>(), ["d", "c"]);
+        assert_eq!(map.values().copied().collect::<Vec<_>>(), [4, 3]);
+        assert_eq!(map.get(&"c"), Some(&3));
+    }
+
+    #[test]
+    fn key_at_inverts_index() {
+        let mut set = DenseSet::new();
+        for key in [10, 20, 30, 40] {
+            set.insert(key);
+        }
+        set.swap_remove(&20);
+
+        for (index, key) in set.iter_indexed() {
+            assert_eq!(set.index(key), Some(index));
+            assert_eq!(set.key_at(index), Some(key));
+        }
+        assert_eq!(set.key_at(set.len()), None);
+    }
+}
@@ -0,0 +1,61 @@
+use std::{
+    any::TypeId,
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+/// Hashes a type into 128 bits. The low half comes from its `TypeId` and the high
+/// half from its name, so two types only share an id if both inputs collide.
+pub fn type_hash<T: ?Sized + 'static>() -> u128 {
+    let mut low = DefaultHasher::new();
+    TypeId::of::<T>().hash(&mut low);
+
+    let mut high = DefaultHasher::new();
+    std::any::type_name::<T>().hash(&mut high);
+
+    (u128::from(high.finish()) << 64) | u128::from(low.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    macro_rules! array_hashes {
+        ($($len:literal)*) => {
+            [$(
+                type_hash::<[u8; $len]>(),
+                type_hash::<[u16; $len]>(),
+                type_hash::<[u32; $len]>(),
+                type_hash::<[u64; $len]>(),
+            )*]
+        };
+    }
+
+    #[test]
+    fn distinct_types_get_distinct_hashes() {
+        let hashes = array_hashes!(0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32 33 34 35 36 37 38 39 40 41 42 43 44 45 46 47 48 49 50 51 52 53 54 55 56 57 58 59 60 61 62 63);
+        let unique = hashes.iter().collect::<HashSet<_>>();
+        assert_eq!(unique.len(), hashes.len());
+
+        let halves = hashes.iter().map(|hash| (hash >> 64) as u64);
+        assert_eq!(halves.collect::<HashSet<_>>().len(), hashes.len());
+        assert_eq!(type_hash::<[u8; 4]>(), hashes[16]);
+    }
+
+    #[test]
+    fn halves_hash_independent_inputs() {
+        fn half<H: Hash + ?Sized>(value: &H) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let hash = type_hash::<Vec<String>>();
+        assert_eq!(hash as u64, half(&TypeId::of::<Vec<String>>()));
+        assert_eq!(
+            (hash >> 64) as u64,
+            half(std::any::type_name::<Vec<String>>())
+        );
+    }
+}
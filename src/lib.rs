@@ -0,0 +1,6 @@
+pub mod asset;
+pub mod background;
+pub mod blob;
+pub mod dense;
+pub mod hash;
+pub mod table;
@@ -1,7 +1 @@
-pub mod asset;
-pub mod background;
-pub mod blob;
-pub mod dense;
-pub mod table;
-
 fn main() {}
@@ -1,9 +1,8 @@
-use crate::blob::Blob;
-use std::{
-    any::TypeId,
-    collections::{hash_map::DefaultHasher, HashMap},
-    hash::{Hash, Hasher},
+use crate::{
+    blob::{Blob, BlobIter, BlobIterMut},
+    hash::type_hash,
 };
+use std::collections::HashMap;
 
 pub struct ColumnCell {
     data: Blob,
@@ -17,11 +16,23 @@ impl ColumnCell {
         Self { data }
     }
 
+    pub fn from_clone<T: Clone + 'static>(value: T) -> Self {
+        let mut data = Blob::new_clone::<T>(1);
+        data.push(value);
+
+        Self { data }
+    }
+
+    pub fn try_clone(&self) -> Option<ColumnCell> {
+        let data = self.data.try_clone()?;
+        Some(Self { data })
+    }
+
     pub fn value<T: 'static>(&self) -> Option<&T> {
         self.data.get::<T>(0)
     }
 
-    pub fn value_mut<T: 'static>(&self) -> Option<&mut T> {
+    pub fn value_mut<T: 'static>(&mut self) -> Option<&mut T> {
         self.data.get_mut::<T>(0)
     }
 
@@ -43,26 +54,59 @@ impl<'a> SelectedCell<'a> {
     pub fn value<T: 'static>(&self) -> Option<&T> {
         self.column.get::<T>(self.index)
     }
+}
+
+pub struct SelectedCellMut<'a> {
+    column: &'a mut Column,
+    index: usize,
+}
+
+impl<'a> SelectedCellMut<'a> {
+    fn new(column: &'a mut Column, index: usize) -> Self {
+        Self { column, index }
+    }
+
+    pub fn value<T: 'static>(&self) -> Option<&T> {
+        self.column.get::<T>(self.index)
+    }
 
-    pub fn value_mut<T: 'static>(&self) -> Option<&mut T> {
+    pub fn value_mut<T: 'static>(&mut self) -> Option<&mut T> {
         self.column.get_mut::<T>(self.index)
     }
 }
 
 pub struct Column {
     data: Blob,
+    default: Option<fn() -> ColumnCell>,
 }
 
 impl Column {
     pub fn new<T: 'static>() -> Self {
         Self {
             data: Blob::new::<T>(0),
+            default: None,
+        }
+    }
+
+    pub fn new_clone<T: Clone + 'static>() -> Self {
+        Self {
+            data: Blob::new_clone::<T>(0),
+            default: None,
+        }
+    }
+
+    /// Creates a column that can produce [`Default`] cells with [`Column::default_cell`].
+    pub fn new_default<T: Default + 'static>() -> Self {
+        Self {
+            data: Blob::new::<T>(0),
+            default: Some(|| ColumnCell::from(T::default())),
         }
     }
 
     pub fn copy(column: &Column) -> Self {
         Column {
-            data: Blob::with_layout(column.data.layout().clone(), 0, column.data.drop().copied()),
+            data: column.data.with_same_layout(0),
+            default: column.default,
         }
     }
 
@@ -70,10 +114,18 @@ impl Column {
         self.data.get::<T>(index)
     }
 
-    pub fn get_mut<T: 'static>(&self, index: usize) -> Option<&mut T> {
+    pub fn get_mut<T: 'static>(&mut self, index: usize) -> Option<&mut T> {
         self.data.get_mut::<T>(index)
     }
 
+    pub fn iter<T: 'static>(&self) -> BlobIter<'_, T> {
+        self.data.iter::<T>()
+    }
+
+    pub fn iter_mut<T: 'static>(&mut self) -> BlobIterMut<'_, T> {
+        self.data.iter_mut::<T>()
+    }
+
     pub fn push<T: 'static>(&mut self, value: T) {
         self.data.push(value)
     }
@@ -94,7 +146,7 @@ impl Column {
         self.data.swap_remove(index)
     }
 
-    pub fn select(&self, index: usize) -> Option<SelectedCell> {
+    pub fn select(&self, index: usize) -> Option<SelectedCell<'_>> {
         if index >= self.len() {
             None
         } else {
@@ -102,6 +154,14 @@ impl Column {
         }
     }
 
+    pub fn select_mut(&mut self, index: usize) -> Option<SelectedCellMut<'_>> {
+        if index >= self.len() {
+            None
+        } else {
+            Some(SelectedCellMut::new(self, index))
+        }
+    }
+
     pub fn push_cell(&mut self, cell: ColumnCell) {
         self.data.extend(cell.data)
     }
@@ -120,6 +180,22 @@ impl Column {
         ColumnCell { data }
     }
 
+    pub fn has_default(&self) -> bool {
+        self.default.is_some()
+    }
+
+    /// Returns a default cell, if the column was created with [`Column::new_default`].
+    pub fn default_cell(&self) -> Option<ColumnCell> {
+        self.default.map(|default| default())
+    }
+
+    /// Clones the cell at `index`. Returns `None` if the column was not created
+    /// with [`Column::new_clone`] or the index is out of bounds.
+    pub fn try_clone_data(&self, index: usize) -> Option<ColumnCell> {
+        let data = self.data.try_clone_at(index)?;
+        Some(ColumnCell { data })
+    }
+
     pub fn len(&self) -> usize {
         self.data.len()
     }
@@ -128,6 +204,18 @@ impl Column {
         self.data.len() == 0
     }
 
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.data.reserve(additional)
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.data.shrink_to_fit()
+    }
+
     pub fn clear(&mut self) {
         self.data.clear()
     }
@@ -135,7 +223,10 @@ impl Column {
 
 impl From<ColumnCell> for Column {
     fn from(cell: ColumnCell) -> Self {
-        Column { data: cell.data }
+        Column {
+            data: cell.data,
+            default: None,
+        }
     }
 }
 
@@ -146,14 +237,11 @@ pub trait ColumnType: 'static {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct ColumnKey(u64);
+pub struct ColumnKey(u128);
 
 impl ColumnKey {
     pub fn from<K: 'static>() -> Self {
-        let mut hasher = DefaultHasher::new();
-        TypeId::of::<K>().hash(&mut hasher);
-
-        ColumnKey(hasher.finish())
+        ColumnKey(type_hash::<K>())
     }
 }
 
@@ -176,7 +264,7 @@ impl Row {
 
     pub fn remove_type<C: ColumnType>(&mut self) -> Option<C::Type> {
         let key = ColumnKey::from::<C>();
-        self.columns.remove(&key)?.take()
+        Some(self.columns.remove(&key)?.take())
     }
 
     pub fn add_field<C: 'static>(&mut self, value: C) -> &mut Self {
@@ -187,7 +275,7 @@ impl Row {
 
     pub fn remove_field<C: 'static>(&mut self) -> Option<C> {
         let key = ColumnKey::from::<C>();
-        self.columns.remove(&key)?.take()
+        Some(self.columns.remove(&key)?.take())
     }
 
     pub fn add_cell(&mut self, key: ColumnKey, cell: ColumnCell) -> &mut Self {
@@ -206,10 +294,10 @@ impl Row {
 
     pub fn field_mut<C: 'static>(&mut self) -> Option<&mut C> {
         let key = ColumnKey::from::<C>();
-        self.columns.get(&key)?.value_mut::<C>()
+        self.columns.get_mut(&key)?.value_mut::<C>()
     }
 
-    pub fn fields(&self) -> std::collections::hash_map::Keys<ColumnKey, ColumnCell> {
+    pub fn fields(&self) -> std::collections::hash_map::Keys<'_, ColumnKey, ColumnCell> {
         self.columns.keys()
     }
 
@@ -218,9 +306,9 @@ impl Row {
         self.columns.get(&key)?.value::<C::Type>()
     }
 
-    pub fn field_type_mut<C: ColumnType>(&self) -> Option<&mut C::Type> {
+    pub fn field_type_mut<C: ColumnType>(&mut self) -> Option<&mut C::Type> {
         let key = ColumnKey::from::<C>();
-        self.columns.get(&key)?.value_mut::<C::Type>()
+        self.columns.get_mut(&key)?.value_mut::<C::Type>()
     }
 
     pub fn cell(&self, key: &ColumnKey) -> Option<&ColumnCell> {
@@ -228,6 +316,39 @@ impl Row {
     }
 }
 
+pub trait Bundle {
+    fn into_row(self) -> Row;
+}
+
+impl Bundle for Row {
+    fn into_row(self) -> Row {
+        self
+    }
+}
+
+macro_rules! impl_bundle {
+    ($($name:ident),*) => {
+        #[allow(non_snake_case)]
+        impl<$($name: 'static),*> Bundle for ($($name,)*) {
+            fn into_row(self) -> Row {
+                let ($($name,)*) = self;
+                let mut row = Row::new();
+                $(row.add_field($name);)*
+                row
+            }
+        }
+    };
+}
+
+impl_bundle!(A);
+impl_bundle!(A, B);
+impl_bundle!(A, B, C);
+impl_bundle!(A, B, C, D);
+impl_bundle!(A, B, C, D, E);
+impl_bundle!(A, B, C, D, E, F);
+impl_bundle!(A, B, C, D, E, F, G);
+impl_bundle!(A, B, C, D, E, F, G, H);
+
 pub struct SelectedRow<'a> {
     columns: HashMap<ColumnKey, &'a Column>,
     index: usize,
@@ -243,12 +364,132 @@ impl<'a> SelectedRow<'a> {
         self.columns.get(&key)?.get::<C>(self.index)
     }
 
-    pub fn field_mut<C: 'static>(&self) -> Option<&mut C> {
+    pub fn fields(&self) -> std::collections::hash_map::Keys<'_, ColumnKey, &'a Column> {
+        self.columns.keys()
+    }
+
+    /// Clones every cell into an owned [`Row`]. Returns `None` if any column is
+    /// not cloneable.
+    pub fn to_row(&self) -> Option<Row> {
+        let mut row = Row::new();
+        for (key, column) in &self.columns {
+            row.add_cell(*key, column.try_clone_data(self.index)?);
+        }
+
+        Some(row)
+    }
+}
+
+pub struct SelectedRowMut<'a> {
+    columns: HashMap<ColumnKey, &'a mut Column>,
+    index: usize,
+}
+
+impl<'a> SelectedRowMut<'a> {
+    pub fn new(columns: HashMap<ColumnKey, &'a mut Column>, index: usize) -> Self {
+        Self { columns, index }
+    }
+
+    pub fn field<C: 'static>(&self) -> Option<&C> {
+        let key = ColumnKey::from::<C>();
+        self.columns.get(&key)?.get::<C>(self.index)
+    }
+
+    pub fn field_mut<C: 'static>(&mut self) -> Option<&mut C> {
         let key = ColumnKey::from::<C>();
-        self.columns.get(&key)?.get_mut::<C>(self.index)
+        self.columns.get_mut(&key)?.get_mut::<C>(self.index)
     }
 
-    pub fn fields(&self) -> std::collections::hash_map::Keys<ColumnKey, &'a Column> {
+    pub fn fields(&self) -> std::collections::hash_map::Keys<'_, ColumnKey, &'a mut Column> {
         self.columns.keys()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn dropping_a_column_drops_its_cells() {
+        static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+        struct Tracked;
+
+        impl Drop for Tracked {
+            fn drop(&mut self) {
+                DROPS.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let mut column = Column::new::<Tracked>();
+        for _ in 0..5 {
+            column.push(Tracked);
+        }
+        drop(column.remove::<Tracked>(0));
+        assert_eq!(DROPS.load(Ordering::SeqCst), 1);
+
+        column.clear();
+        assert_eq!(DROPS.load(Ordering::SeqCst), 5);
+
+        column.push(Tracked);
+        column.push(Tracked);
+        drop(column);
+        assert_eq!(DROPS.load(Ordering::SeqCst), 7);
+    }
+
+    #[test]
+    fn typed_access_checks_the_stored_type() {
+        let mut column = Column::new::<u32>();
+        column.push(7u32);
+
+        assert_eq!(column.get::<u32>(0), Some(&7));
+        assert_eq!(column.get::<f32>(0), None);
+        assert_eq!(column.get_mut::<f32>(0), None);
+    }
+
+    #[test]
+    fn cloned_cells_are_independent() {
+        assert!(ColumnCell::from(String::from("plain"))
+            .try_clone()
+            .is_none());
+
+        let original = ColumnCell::from_clone(String::from("name"));
+        let mut copy = original.try_clone().unwrap();
+        copy.value_mut::<String>().unwrap().push_str("-copy");
+
+        assert_eq!(original.value::<String>().unwrap(), "name");
+        assert_eq!(copy.value::<String>().unwrap(), "name-copy");
+    }
+
+    #[test]
+    fn typed_iterators_visit_every_value() {
+        let mut column = Column::new::<u32>();
+        for value in [1u32, 2, 3] {
+            column.push(value);
+        }
+        assert_eq!(column.iter::<u32>().sum::<u32>(), 6);
+
+        for value in column.iter_mut::<u32>() {
+            *value *= 10;
+        }
+        assert_eq!(
+            column.iter::<u32>().copied().collect::<Vec<_>>(),
+            [10, 20, 30]
+        );
+    }
+
+    #[test]
+    fn zero_sized_columns_count_elements() {
+        let mut column = Column::new::<()>();
+        for _ in 0..3 {
+            column.push(());
+        }
+
+        assert_eq!(column.len(), 3);
+        assert_eq!(column.get::<()>(2), Some(&()));
+        assert_eq!(column.iter::<()>().count(), 3);
+        column.remove::<()>(0);
+        assert_eq!(column.len(), 2);
+    }
+}
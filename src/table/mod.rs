@@ -1,8 +1,13 @@
 use crate::dense::DenseSet;
-use column::{Column, ColumnKey, ColumnType, Row, SelectedCell, SelectedRow};
+use column::{
+    Bundle, Column, ColumnCell, ColumnKey, ColumnType, Row, SelectedCell, SelectedCellMut,
+    SelectedRow, SelectedRowMut,
+};
+use query::Query;
 use std::{collections::HashMap, hash::Hash};
 
 pub mod column;
+pub mod query;
 
 #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
 pub struct RowIndex {
@@ -24,38 +29,70 @@ impl RowIndex {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TableError {
+    MissingColumn(String),
+    RowExists(RowIndex),
+}
+
+impl std::fmt::Display for TableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TableError::MissingColumn(name) => write!(f, "Row is missing column {}", name),
+            TableError::RowExists(index) => write!(f, "Row {} already exists", index.id),
+        }
+    }
+}
+
+impl std::error::Error for TableError {}
+
 pub struct TableLayout {
     columns: HashMap<ColumnKey, Column>,
+    names: HashMap<ColumnKey, &'static str>,
 }
 
 impl TableLayout {
     pub fn new() -> Self {
         Self {
             columns: HashMap::new(),
+            names: HashMap::new(),
         }
     }
 
     pub fn add_type<C: ColumnType>(&mut self) -> &mut Self {
         let key = ColumnKey::from::<C>();
         self.columns.insert(key, Column::new::<C::Type>());
+        self.names.insert(key, C::name());
         self
     }
 
     pub fn with_type<C: ColumnType>(mut self) -> Self {
-        let key = ColumnKey::from::<C>();
-        self.columns.insert(key, Column::new::<C::Type>());
+        self.add_type::<C>();
         self
     }
 
     pub fn add_field<C: 'static>(&mut self) -> &mut Self {
         let key = ColumnKey::from::<C>();
         self.columns.insert(key, Column::new::<C>());
+        self.names.insert(key, std::any::type_name::<C>());
         self
     }
 
     pub fn with_field<C: 'static>(mut self) -> Self {
+        self.add_field::<C>();
+        self
+    }
+
+    /// Adds a field column that [`Table::migrate_row`] can fill with `C::default()`.
+    pub fn add_default_field<C: Default + 'static>(&mut self) -> &mut Self {
         let key = ColumnKey::from::<C>();
-        self.columns.insert(key, Column::new::<C>());
+        self.columns.insert(key, Column::new_default::<C>());
+        self.names.insert(key, std::any::type_name::<C>());
+        self
+    }
+
+    pub fn with_default_field<C: Default + 'static>(mut self) -> Self {
+        self.add_default_field::<C>();
         self
     }
 
@@ -72,14 +109,18 @@ impl TableLayout {
     pub fn build(self) -> Table {
         Table {
             columns: self.columns,
+            names: self.names,
             rows: DenseSet::new(),
+            generations: HashMap::new(),
         }
     }
 }
 
 pub struct Table {
     columns: HashMap<ColumnKey, Column>,
-    rows: DenseSet<RowIndex>,
+    names: HashMap<ColumnKey, &'static str>,
+    rows: DenseSet<usize>,
+    generations: HashMap<usize, usize>,
 }
 
 impl Table {
@@ -87,46 +128,57 @@ impl Table {
         TableLayout::new()
     }
 
+    /// Resolves a row index to its dense position, rejecting stale generations.
+    fn row(&self, index: RowIndex) -> Option<usize> {
+        match self.generations.get(&index.id) {
+            Some(gen) if *gen == index.gen => self.rows.index(&index.id),
+            _ => None,
+        }
+    }
+
     pub fn field<C: 'static>(&self, index: impl Into<RowIndex>) -> Option<&C> {
         let key = ColumnKey::from::<C>();
-        let index = index.into();
-        let index = self.rows.index(&index)?;
+        let index = self.row(index.into())?;
         self.columns.get(&key)?.get::<C>(index)
     }
 
-    pub fn field_mut<C: 'static>(&self, index: impl Into<RowIndex>) -> Option<&mut C> {
+    pub fn field_mut<C: 'static>(&mut self, index: impl Into<RowIndex>) -> Option<&mut C> {
         let key = ColumnKey::from::<C>();
-        let index = index.into();
-        let index = self.rows.index(&index)?;
-        self.columns.get(&key)?.get_mut::<C>(index)
+        let index = self.row(index.into())?;
+        self.columns.get_mut(&key)?.get_mut::<C>(index)
     }
 
     pub fn field_type<C: ColumnType>(&self, index: impl Into<RowIndex>) -> Option<&C::Type> {
         let key = ColumnKey::from::<C>();
-        let index = index.into();
-        let index = self.rows.index(&index)?;
+        let index = self.row(index.into())?;
         self.columns.get(&key)?.get::<C::Type>(index)
     }
 
     pub fn field_type_mut<C: ColumnType>(
-        &self,
+        &mut self,
         index: impl Into<RowIndex>,
     ) -> Option<&mut C::Type> {
         let key = ColumnKey::from::<C>();
-        let index = index.into();
-        let index = self.rows.index(&index)?;
-        self.columns.get(&key)?.get_mut::<C::Type>(index)
+        let index = self.row(index.into())?;
+        self.columns.get_mut(&key)?.get_mut::<C::Type>(index)
     }
 
-    pub fn cell(&self, key: &ColumnKey, index: impl Into<RowIndex>) -> Option<SelectedCell> {
-        let index = index.into();
-        let index = self.rows.index(&index)?;
+    pub fn cell(&self, key: &ColumnKey, index: impl Into<RowIndex>) -> Option<SelectedCell<'_>> {
+        let index = self.row(index.into())?;
         self.columns.get(key)?.select(index)
     }
 
-    pub fn select(&self, index: impl Into<RowIndex>) -> Option<SelectedRow> {
-        let index = index.into();
-        let index = self.rows.index(&index)?;
+    pub fn cell_mut(
+        &mut self,
+        key: &ColumnKey,
+        index: impl Into<RowIndex>,
+    ) -> Option<SelectedCellMut<'_>> {
+        let index = self.row(index.into())?;
+        self.columns.get_mut(key)?.select_mut(index)
+    }
+
+    pub fn select(&self, index: impl Into<RowIndex>) -> Option<SelectedRow<'_>> {
+        let index = self.row(index.into())?;
         let mut columns = HashMap::new();
         for (field, column) in &self.columns {
             columns.insert(field.clone(), column);
@@ -135,8 +187,142 @@ impl Table {
         Some(SelectedRow::new(columns, index))
     }
 
-    pub fn insert(&mut self, index: impl Into<RowIndex>, mut row: Row) {
-        self.rows.insert(index.into());
+    pub fn select_mut(&mut self, index: impl Into<RowIndex>) -> Option<SelectedRowMut<'_>> {
+        let index = self.row(index.into())?;
+        let mut columns = HashMap::new();
+        for (field, column) in &mut self.columns {
+            columns.insert(*field, column);
+        }
+
+        Some(SelectedRowMut::new(columns, index))
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    pub fn contains(&self, index: impl Into<RowIndex>) -> bool {
+        self.row(index.into()).is_some()
+    }
+
+    pub fn has_column(&self, key: &ColumnKey) -> bool {
+        self.columns.contains_key(key)
+    }
+
+    pub fn column(&self, key: &ColumnKey) -> Option<&Column> {
+        self.columns.get(key)
+    }
+
+    pub fn column_keys(&self) -> impl Iterator<Item = ColumnKey> + '_ {
+        self.columns.keys().copied()
+    }
+
+    pub fn column_count(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// The name a column was registered with: `ColumnType::name` for typed
+    /// columns, the type name for fields, and `None` for raw columns.
+    pub fn column_name(&self, key: &ColumnKey) -> Option<&'static str> {
+        self.names.get(key).copied()
+    }
+
+    fn describe_column(&self, key: &ColumnKey) -> String {
+        match self.column_name(key) {
+            Some(name) => name.to_string(),
+            None => format!("{:?}", key),
+        }
+    }
+
+    /// Adds a column after the table is built, filling it with `default` up to the
+    /// current row count.
+    pub fn add_column(
+        &mut self,
+        key: ColumnKey,
+        mut column: Column,
+        mut default: impl FnMut() -> ColumnCell,
+    ) {
+        if column.len() > self.rows.len() {
+            panic!("Column has more cells than the table has rows.")
+        }
+
+        while column.len() < self.rows.len() {
+            column.push_cell(default());
+        }
+
+        self.columns.insert(key, column);
+    }
+
+    pub fn add_field<C: Default + 'static>(&mut self) {
+        let key = ColumnKey::from::<C>();
+        self.add_column(key, Column::new::<C>(), || ColumnCell::from(C::default()));
+        self.names.insert(key, std::any::type_name::<C>());
+    }
+
+    pub fn remove_column(&mut self, key: &ColumnKey) -> Option<Column> {
+        self.names.remove(key);
+        self.columns.remove(key)
+    }
+
+    pub fn row_indices(&self) -> impl Iterator<Item = RowIndex> + '_ {
+        self.rows
+            .iter()
+            .map(|id| RowIndex::new(*id, self.generations[id]))
+    }
+
+    /// Iterates every row that has all of the queried fields.
+    pub fn query<Q: Query>(&self) -> impl Iterator<Item = Q::Item<'_>> {
+        (0..self.rows.len()).filter_map(|index| Q::fetch(self, index))
+    }
+
+    /// Inserts a row, replacing any row already stored under the same id.
+    /// Panics if the row lacks one of the table's columns.
+    pub fn insert(&mut self, index: impl Into<RowIndex>, row: impl Bundle) {
+        let index = index.into();
+        let row = row.into_row();
+        if let Err(error) = self.check_columns(&row) {
+            panic!("{}", error);
+        }
+
+        if let Some(gen) = self.generations.get(&index.id).copied() {
+            self.remove(RowIndex::new(index.id, gen));
+        }
+
+        self.push_row(index, row);
+    }
+
+    /// Inserts a row into an unused id. Fails without touching the table if the
+    /// id is taken or the row lacks one of the table's columns.
+    pub fn try_insert(
+        &mut self,
+        index: impl Into<RowIndex>,
+        row: impl Bundle,
+    ) -> Result<(), TableError> {
+        let index = index.into();
+        let row = row.into_row();
+        if self.rows.contains(&index.id) {
+            return Err(TableError::RowExists(index));
+        }
+
+        self.check_columns(&row)?;
+        self.push_row(index, row);
+        Ok(())
+    }
+
+    fn check_columns(&self, row: &Row) -> Result<(), TableError> {
+        match self.columns.keys().find(|key| row.cell(key).is_none()) {
+            Some(key) => Err(TableError::MissingColumn(self.describe_column(key))),
+            None => Ok(()),
+        }
+    }
+
+    fn push_row(&mut self, index: RowIndex, mut row: Row) {
+        self.rows.insert(index.id);
+        self.generations.insert(index.id, index.gen);
         for (field, column) in &mut self.columns {
             let cell = row.remove_cell(field).unwrap();
             column.push_cell(cell);
@@ -145,8 +331,9 @@ impl Table {
 
     pub fn remove(&mut self, index: impl Into<RowIndex>) -> Option<Row> {
         let index = index.into();
-        let idx = self.rows.index(&index)?;
-        self.rows.remove(&index)?;
+        let idx = self.row(index)?;
+        self.rows.swap_remove(&index.id)?;
+        self.generations.remove(&index.id);
         let mut row = Row::new();
         for (field, column) in &mut self.columns {
             let cell = column.swap_remove_data(idx);
@@ -156,10 +343,400 @@ impl Table {
         Some(row)
     }
 
+    /// Moves a row into `target`, dropping any cells that `target` has no column for.
+    /// Columns that `target` has and this table lacks are left default. Returns `None`
+    /// without moving anything if the row is missing, `target` already has a row with
+    /// its id, or one of those columns was not added with a default.
+    pub fn migrate_row(
+        &mut self,
+        index: impl Into<RowIndex>,
+        target: &mut Table,
+    ) -> Option<RowIndex> {
+        self.migrate_row_with(index, target, Row::new())
+    }
+
+    /// Like [`Table::migrate_row`], but takes columns that this table lacks from
+    /// `fill` before falling back to their defaults. `fill` never replaces a
+    /// migrated cell.
+    pub fn migrate_row_with(
+        &mut self,
+        index: impl Into<RowIndex>,
+        target: &mut Table,
+        fill: impl Bundle,
+    ) -> Option<RowIndex> {
+        let index = index.into();
+        let mut fill = fill.into_row();
+        if !self.contains(index)
+            || target.rows.contains(&index.id)
+            || target.columns.iter().any(|(key, column)| {
+                !self.has_column(key) && fill.cell(key).is_none() && !column.has_default()
+            })
+        {
+            return None;
+        }
+
+        let mut row = self.remove(index)?;
+        for (key, column) in &target.columns {
+            if row.cell(key).is_none() {
+                let cell = fill.remove_cell(key).or_else(|| column.default_cell())?;
+                row.add_cell(*key, cell);
+            }
+        }
+        target.push_row(index, row);
+
+        Some(index)
+    }
+
+    /// Removes every row, keeping column capacity so the table can be refilled
+    /// without reallocating.
     pub fn clear(&mut self) {
         self.rows.clear();
+        self.generations.clear();
         for column in self.columns.values_mut() {
             column.clear();
         }
     }
+
+    /// Removes every row and releases the memory the columns were holding.
+    pub fn reset(&mut self) {
+        self.clear();
+        self.generations.shrink_to_fit();
+        for column in self.columns.values_mut() {
+            column.shrink_to_fit();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Position(f32);
+
+    #[derive(Debug, PartialEq)]
+    struct Velocity(f32);
+
+    #[derive(Debug, PartialEq)]
+    struct Health(u32);
+
+    #[test]
+    fn stale_indices_stop_resolving() {
+        let mut table = Table::builder().with_field::<Position>().build();
+        let old = RowIndex::new(0, 0);
+        let new = RowIndex::new(0, 1);
+        table.insert(old, (Position(1.0),));
+        table.remove(old).unwrap();
+        table.insert(new, (Position(2.0),));
+
+        assert!(!table.contains(old));
+        assert_eq!(table.field::<Position>(old), None);
+        assert!(table.cell(&ColumnKey::from::<Position>(), old).is_none());
+        assert!(table.select(old).is_none());
+        assert_eq!(table.field::<Position>(new), Some(&Position(2.0)));
+    }
+
+    #[test]
+    fn removing_rows_forgets_generations() {
+        let mut table = Table::builder().with_field::<Position>().build();
+        for id in 0..4 {
+            table.insert(RowIndex::new(id, 0), (Position(id as f32),));
+        }
+        table.remove(RowIndex::new(1, 0)).unwrap();
+        assert_eq!(table.generations.len(), 3);
+
+        table.clear();
+        assert!(table.generations.is_empty());
+        assert!(!table.contains(RowIndex::new(0, 0)));
+    }
+
+    #[test]
+    fn migrate_row_keeps_shared_columns() {
+        let mut source = Table::builder()
+            .with_field::<Position>()
+            .with_field::<Velocity>()
+            .build();
+        let mut target = Table::builder().with_field::<Position>().build();
+        let index = RowIndex::new(0, 0);
+        source.insert(index, (Position(1.0), Velocity(2.0)));
+
+        assert_eq!(source.migrate_row(index, &mut target), Some(index));
+        assert!(!source.contains(index));
+        assert_eq!(target.field::<Position>(index), Some(&Position(1.0)));
+        assert_eq!(target.field::<Velocity>(index), None);
+    }
+
+    #[test]
+    fn migrate_row_fills_missing_columns() {
+        let mut source = Table::builder().with_field::<Position>().build();
+        let mut target = Table::builder()
+            .with_field::<Position>()
+            .with_field::<Health>()
+            .build();
+        let index = RowIndex::new(0, 0);
+        source.insert(index, (Position(1.0),));
+
+        assert_eq!(source.migrate_row(index, &mut target), None);
+        assert!(source.contains(index));
+
+        let fill = (Position(5.0), Health(10));
+        assert_eq!(
+            source.migrate_row_with(index, &mut target, fill),
+            Some(index)
+        );
+        assert_eq!(target.field::<Position>(index), Some(&Position(1.0)));
+        assert_eq!(target.field::<Health>(index), Some(&Health(10)));
+    }
+
+    #[derive(Debug, Default, PartialEq)]
+    struct Armor(u32);
+
+    #[test]
+    fn migrate_row_defaults_target_only_columns() {
+        let mut source = Table::builder().with_field::<Position>().build();
+        let mut target = Table::builder()
+            .with_field::<Position>()
+            .with_default_field::<Armor>()
+            .build();
+        let index = RowIndex::new(0, 0);
+        source.insert(index, (Position(1.0),));
+
+        assert_eq!(source.migrate_row(index, &mut target), Some(index));
+        assert_eq!(target.field::<Position>(index), Some(&Position(1.0)));
+        assert_eq!(target.field::<Armor>(index), Some(&Armor(0)));
+    }
+
+    #[test]
+    fn migrate_row_rejects_taken_ids() {
+        let mut source = Table::builder().with_field::<Position>().build();
+        let mut target = Table::builder().with_field::<Position>().build();
+        let index = RowIndex::new(0, 0);
+        source.insert(index, (Position(1.0),));
+        target.insert(index, (Position(2.0),));
+
+        assert_eq!(source.migrate_row(index, &mut target), None);
+        assert_eq!(source.field::<Position>(index), Some(&Position(1.0)));
+        assert_eq!(target.field::<Position>(index), Some(&Position(2.0)));
+    }
+
+    #[test]
+    fn remove_keeps_rows_and_columns_in_step() {
+        let mut table = Table::builder()
+            .with_field::<Position>()
+            .with_field::<Health>()
+            .build();
+        for id in 0..3 {
+            table.insert(
+                RowIndex::new(id, 0),
+                (Position(id as f32), Health(id as u32)),
+            );
+        }
+
+        table.remove(RowIndex::new(0, 0)).unwrap();
+        for id in 1..3 {
+            let index = RowIndex::new(id, 0);
+            assert_eq!(table.field::<Position>(index), Some(&Position(id as f32)));
+            assert_eq!(table.field::<Health>(index), Some(&Health(id as u32)));
+        }
+    }
+
+    #[test]
+    fn query_visits_rows_with_every_field() {
+        let mut table = Table::builder()
+            .with_field::<Position>()
+            .with_field::<Velocity>()
+            .build();
+        for id in 0..3 {
+            table.insert(
+                RowIndex::new(id, 0),
+                (Position(id as f32), Velocity(id as f32 * 10.0)),
+            );
+        }
+
+        let mut rows = table
+            .query::<(Position, Velocity)>()
+            .map(|(position, velocity)| (position.0, velocity.0))
+            .collect::<Vec<_>>();
+        rows.sort_by(|a, b| a.0.total_cmp(&b.0));
+        assert_eq!(rows, [(0.0, 0.0), (1.0, 10.0), (2.0, 20.0)]);
+        assert_eq!(table.query::<(Position, Health)>().count(), 0);
+    }
+
+    #[test]
+    fn row_indices_visit_every_row() {
+        let mut table = Table::builder().with_field::<Health>().build();
+        assert!(table.is_empty());
+        let indices = [
+            RowIndex::new(0, 0),
+            RowIndex::new(4, 2),
+            RowIndex::new(7, 1),
+        ];
+        for index in indices {
+            table.insert(index, (Health(1),));
+        }
+
+        assert_eq!(table.len(), 3);
+        assert!(indices.iter().all(|index| table.contains(*index)));
+        assert!(!table.contains(RowIndex::new(4, 0)));
+
+        let mut visited = table.row_indices().collect::<Vec<_>>();
+        visited.sort_by_key(|index| index.id());
+        assert_eq!(visited, indices);
+    }
+
+    #[test]
+    fn inserts_tuple_bundles() {
+        let mut table = Table::builder()
+            .with_field::<Position>()
+            .with_field::<Health>()
+            .build();
+        let index = RowIndex::new(0, 0);
+        table.insert(index, (Health(100), Position(3.0)));
+
+        assert_eq!(table.field::<Position>(index), Some(&Position(3.0)));
+        assert_eq!(table.field::<Health>(index), Some(&Health(100)));
+    }
+
+    #[test]
+    fn reports_its_layout() {
+        let table = Table::builder()
+            .with_field::<Position>()
+            .with_field::<Health>()
+            .build();
+
+        assert_eq!(table.column_count(), 2);
+        assert!(table.has_column(&ColumnKey::from::<Position>()));
+        assert!(table.has_column(&ColumnKey::from::<Health>()));
+        assert!(!table.has_column(&ColumnKey::from::<Velocity>()));
+
+        let keys = table.column_keys().collect::<Vec<_>>();
+        assert_eq!(keys.len(), 2);
+        assert!(keys.contains(&ColumnKey::from::<Position>()));
+        assert!(keys.contains(&ColumnKey::from::<Health>()));
+    }
+
+    #[test]
+    fn added_columns_are_backfilled() {
+        let mut table = Table::builder().with_field::<Position>().build();
+        for id in 0..3 {
+            table.insert(RowIndex::new(id, 0), (Position(id as f32),));
+        }
+
+        table.add_column(ColumnKey::from::<Health>(), Column::new::<Health>(), || {
+            ColumnCell::from(Health(50))
+        });
+        for index in table.row_indices().collect::<Vec<_>>() {
+            assert_eq!(table.field::<Health>(index), Some(&Health(50)));
+        }
+
+        assert!(table.remove_column(&ColumnKey::from::<Health>()).is_some());
+        assert!(!table.has_column(&ColumnKey::from::<Health>()));
+    }
+
+    struct Player;
+
+    impl ColumnType for Player {
+        type Type = u32;
+
+        fn name() -> &'static str {
+            "Player"
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Row is missing column Player")]
+    fn missing_column_panic_names_the_column() {
+        let mut table = Table::builder()
+            .with_field::<Position>()
+            .with_type::<Player>()
+            .build();
+        assert_eq!(
+            table.column_name(&ColumnKey::from::<Player>()),
+            Some("Player")
+        );
+
+        table.insert(RowIndex::new(0, 0), (Position(1.0),));
+    }
+
+    #[test]
+    fn try_insert_rejects_bad_rows_without_changes() {
+        let mut table = Table::builder()
+            .with_field::<Position>()
+            .with_type::<Player>()
+            .build();
+        let index = RowIndex::new(0, 0);
+
+        let mut row = Row::new();
+        row.add_field(Position(1.0));
+        assert_eq!(
+            table.try_insert(index, row),
+            Err(TableError::MissingColumn("Player".into()))
+        );
+        assert!(table.is_empty());
+        assert!(table
+            .column(&ColumnKey::from::<Position>())
+            .unwrap()
+            .is_empty());
+
+        let mut row = Row::new();
+        row.add_field(Position(1.0)).add_type::<Player>(7);
+        assert_eq!(table.try_insert(index, row), Ok(()));
+
+        let mut row = Row::new();
+        row.add_field(Position(2.0)).add_type::<Player>(8);
+        assert_eq!(
+            table.try_insert(RowIndex::new(0, 1), row),
+            Err(TableError::RowExists(RowIndex::new(0, 1)))
+        );
+        assert_eq!(table.len(), 1);
+        assert_eq!(table.field::<Position>(index), Some(&Position(1.0)));
+        assert_eq!(table.field_type::<Player>(index), Some(&7));
+    }
+
+    #[test]
+    fn clear_keeps_capacity_and_reset_releases_it() {
+        let mut table = Table::builder().with_field::<Health>().build();
+        for id in 0..64 {
+            table.insert(RowIndex::new(id, 0), (Health(id as u32),));
+        }
+        let key = ColumnKey::from::<Health>();
+        let capacity = table.column(&key).unwrap().capacity();
+        assert!(capacity >= 64);
+
+        table.clear();
+        assert_eq!(table.len(), 0);
+        assert_eq!(table.column(&key).unwrap().len(), 0);
+        assert_eq!(table.column(&key).unwrap().capacity(), capacity);
+
+        table.insert(RowIndex::new(0, 0), (Health(1),));
+        table.reset();
+        assert!(table.is_empty());
+        assert!(table.column(&key).unwrap().capacity() < capacity);
+    }
+
+    #[test]
+    fn selected_rows_clone_into_owned_rows() {
+        let mut table = Table::builder()
+            .with_column(ColumnKey::from::<String>(), Column::new_clone::<String>())
+            .with_column(ColumnKey::from::<u32>(), Column::new_clone::<u32>())
+            .build();
+        let index = RowIndex::new(0, 0);
+        table.insert(index, (String::from("player"), 100u32));
+
+        let mut row = table.select(index).unwrap().to_row().unwrap();
+        row.field_mut::<String>().unwrap().push_str("-copy");
+        assert_eq!(row.field::<u32>(), Some(&100));
+
+        let mut copy = Table::builder()
+            .with_column(ColumnKey::from::<String>(), Column::new_clone::<String>())
+            .with_column(ColumnKey::from::<u32>(), Column::new_clone::<u32>())
+            .build();
+        copy.insert(index, row);
+        assert_eq!(copy.field::<String>(index).unwrap(), "player-copy");
+        assert_eq!(table.field::<String>(index).unwrap(), "player");
+
+        let mut plain = Table::builder().with_field::<Health>().build();
+        plain.insert(index, (Health(1),));
+        assert!(plain.select(index).unwrap().to_row().is_none());
+    }
 }
@@ -0,0 +1,27 @@
+use super::{column::ColumnKey, Table};
+
+pub trait Query {
+    type Item<'a>;
+
+    fn fetch(table: &Table, index: usize) -> Option<Self::Item<'_>>;
+}
+
+macro_rules! impl_query {
+    ($($name:ident),*) => {
+        impl<$($name: 'static),*> Query for ($($name,)*) {
+            type Item<'a> = ($(&'a $name,)*);
+
+            fn fetch(table: &Table, index: usize) -> Option<Self::Item<'_>> {
+                Some(($(
+                    table
+                        .columns
+                        .get(&ColumnKey::from::<$name>())?
+                        .get::<$name>(index)?,
+                )*))
+            }
+        }
+    };
+}
+
+impl_query!(A, B);
+impl_query!(A, B, C);
@@ -0,0 +1,5 @@
+#[test]
+fn borrows() {
+    let tests = trybuild::TestCases::new();
+    tests.compile_fail("tests/ui/table/*.rs");
+}
@@ -0,0 +1,7 @@
+use gob::asset::Asset;
+
+#[derive(Asset)]
+#[asset(title = "texture")]
+struct Texture;
+
+fn main() {}
@@ -0,0 +1,9 @@
+use gob::asset::bytes::IntoBytes;
+
+#[derive(IntoBytes)]
+enum Shape {
+    Circle,
+    Square,
+}
+
+fn main() {}
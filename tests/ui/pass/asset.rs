@@ -0,0 +1,14 @@
+use gob::asset::Asset;
+
+#[derive(Asset)]
+struct Texture;
+
+#[derive(Asset)]
+#[asset(name = "mesh")]
+struct Mesh<T: Send + Sync + 'static>(Vec<T>);
+
+fn main() {
+    assert_eq!(Texture::name(), "Texture");
+    assert_eq!(Texture::ASSET_NAME, "Texture");
+    assert_eq!(<Mesh<u8> as Asset>::name(), "mesh");
+}
@@ -0,0 +1,25 @@
+use gob::asset::bytes::IntoBytes;
+
+#[derive(Debug, PartialEq, IntoBytes)]
+struct Header {
+    name: String,
+    version: u32,
+    sections: Vec<u16>,
+}
+
+#[derive(Debug, PartialEq, IntoBytes)]
+struct Pair<T>(T, u8);
+
+fn main() {
+    let header = Header {
+        name: String::from("gob"),
+        version: 3,
+        sections: vec![4, 8, 15],
+    };
+    let bytes = header.into_bytes();
+    assert_eq!(Header::from_bytes(&bytes[..bytes.len() - 1]), None);
+    assert_eq!(Header::from_bytes(&bytes), Some(header));
+
+    let pair = Pair(7u64, 1);
+    assert_eq!(Pair::from_bytes(&pair.into_bytes()), Some(pair));
+}
@@ -0,0 +1,10 @@
+use gob::table::column::Column;
+
+fn main() {
+    let mut column = Column::new::<u32>();
+    column.push(1u32);
+
+    let first = column.get_mut::<u32>(0).unwrap();
+    let second = column.get_mut::<u32>(0).unwrap();
+    *first += *second;
+}
@@ -0,0 +1,14 @@
+use gob::table::{RowIndex, Table};
+
+struct Health(u32);
+
+fn main() {
+    let mut table = Table::builder().with_field::<Health>().build();
+    let index = RowIndex::new(0, 0);
+    table.insert(index, (Health(10),));
+
+    let mut row = table.select_mut(index).unwrap();
+    let first = row.field_mut::<Health>().unwrap();
+    let second = row.field_mut::<Health>().unwrap();
+    first.0 += second.0;
+}